@@ -1,4 +1,9 @@
+use crate::output::export::validate_prefix;
+use chrono::NaiveDate;
 use clap::Parser;
+use lsb_release_rs::format::Template;
+use lsb_release_rs::DistroInfoSource;
+use std::path::PathBuf;
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Parser)]
@@ -15,8 +20,78 @@ pub struct Args {
     pub(crate) show_codename: bool,
     #[clap(short = 'a', long = "all")]
     show_all: bool,
+    #[clap(short = 'u', long = "upstream")]
+    pub(crate) upstream: bool,
     #[clap(short = 's', long = "short")]
     pub(crate) show_in_short_format: bool,
+    /// Print every field as a single JSON object instead of tab-separated lines
+    ///
+    /// The object has the keys `schema_version`, `id`, `description`, `release`, `codename` and
+    /// `lsb_modules`, the last one an array of modules such as `core-4.1-amd64`. Unknown values are
+    /// `null` rather than `n/a`, and `lsb_modules` is `null` if there are none. `schema_version` is
+    /// 1; it only changes when a key is removed or renamed or its type changes, while new keys may
+    /// be added at any time.
+    #[clap(long = "json", conflicts_with = "show-in-short-format")]
+    pub(crate) json: bool,
+    /// Print every field as `KEY='value'` lines suitable for `eval` in a POSIX shell
+    #[clap(long = "export", conflicts_with_all = &["show-in-short-format", "json"])]
+    pub(crate) export: bool,
+    /// Prefix of the variable names printed by `--export`
+    #[clap(
+        long = "export-prefix",
+        default_value = "LSB_",
+        requires = "export",
+        validator = validate_prefix
+    )]
+    pub(crate) export_prefix: String,
+    /// Print the fields through a template, e.g. `{id}-{release} ({codename:-unknown})`
+    #[clap(
+        long = "format",
+        conflicts_with_all = &["show-in-short-format", "json", "export"]
+    )]
+    pub(crate) format: Option<Template>,
+    /// Print the end of regular security support of the release
+    #[clap(long = "eol")]
+    pub(crate) show_eol: bool,
+    /// Print whether the release is supported, in LTS, in ELTS/ESM, or end-of-life, and how many
+    /// days remain
+    #[clap(long = "support-status")]
+    pub(crate) show_support_status: bool,
+    /// Print the codename of the current Debian testing release
+    #[clap(long = "testing-codename")]
+    pub(crate) show_testing_codename: bool,
+    /// Print the release, end-of-life, LTS and ELTS/ESM dates of the release and its successors
+    /// as an iCalendar (.ics) file
+    #[clap(
+        long = "ical",
+        conflicts_with_all = &["show-in-short-format", "json", "export", "format"]
+    )]
+    pub(crate) ical: bool,
+    /// Print the releases to upgrade through, one at a time, to reach TARGET (a codename, version
+    /// or alias such as `testing`) or else the latest stable release; `--json` and `-s` apply
+    #[clap(
+        long = "upgrade-path",
+        value_name = "TARGET",
+        conflicts_with_all = &["export", "format", "ical"]
+    )]
+    // absent, given without a target (the latest stable release) or given with one
+    #[allow(clippy::option_option)]
+    pub(crate) upgrade_path: Option<Option<String>>,
+    /// Inspect the system installed in this directory, such as a chroot or a mounted image; the
+    /// `LSB_*` and `DPKG_ADMINDIR` environment variables are ignored then
+    #[clap(long = "root", default_value = "/", parse(from_os_str))]
+    pub(crate) root: PathBuf,
+    /// Where to read the distro-info tables from: `auto` prefers /usr/share/distro-info and falls
+    /// back to the built-in tables, `system` and `embedded` use only one of them
+    #[clap(long = "distro-info", default_value = "auto")]
+    pub(crate) distro_info_source: DistroInfoSource,
+    /// Evaluate `--support-status`, `--testing-codename` and `--upgrade-path` as of this date
+    /// (YYYY-MM-DD) instead of today
+    #[clap(long = "date")]
+    pub(crate) date: Option<NaiveDate>,
+    /// Explain on stderr where the reported values come from, if anything is unusual
+    #[clap(long = "verbose")]
+    pub(crate) verbose: bool,
 }
 
 impl Args {
@@ -28,11 +103,14 @@ impl Args {
             self.show_release = true;
             self.show_codename = true;
         } else {
-            self.show_lsb_modules = !self.show_lsb_modules
-                && !self.show_distributor
+            // the modules are shown if nothing else is asked for
+            self.show_lsb_modules |= !self.show_distributor
                 && !self.show_description
                 && !self.show_release
-                && !self.show_codename;
+                && !self.show_codename
+                && !self.show_eol
+                && !self.show_support_status
+                && !self.show_testing_codename;
         }
 
         self
@@ -0,0 +1,254 @@
+use chrono::{Local, NaiveDate};
+use clap::{ArgGroup, CommandFactory, FromArgMatches, Parser};
+use lsb_release_rs::{DistroInfoTable, DistroRelease, SupportPhase};
+
+/// The command line of `debian-distro-info` and `ubuntu-distro-info`, which this binary mimics
+/// when it is invoked under one of those names.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Parser)]
+#[clap(group(
+    ArgGroup::new("selection")
+        .required(true)
+        .args(&["all", "devel", "stable", "oldstable", "testing", "lts", "supported", "unsupported"])
+))]
+#[clap(group(ArgGroup::new("output").args(&["codename", "release", "fullname"])))]
+pub struct Args {
+    /// List all known versions
+    #[clap(short = 'a', long = "all")]
+    all: bool,
+    /// Latest development version
+    #[clap(short = 'd', long = "devel")]
+    devel: bool,
+    /// Latest stable version
+    #[clap(short = 's', long = "stable")]
+    stable: bool,
+    /// Latest oldstable version (Debian only)
+    #[clap(short = 'o', long = "oldstable")]
+    oldstable: bool,
+    /// Current testing version (Debian only)
+    #[clap(short = 't', long = "testing")]
+    testing: bool,
+    /// Versions supported by Debian LTS, or the latest Ubuntu LTS version
+    #[clap(long = "lts")]
+    lts: bool,
+    /// Supported versions, including development ones
+    #[clap(long = "supported")]
+    supported: bool,
+    /// Versions which are no longer supported
+    #[clap(long = "unsupported")]
+    unsupported: bool,
+    /// Evaluate the selection as of this date (YYYY-MM-DD) instead of today
+    #[clap(long = "date")]
+    date: Option<NaiveDate>,
+    /// Print the codename (default)
+    #[clap(short = 'c', long = "codename")]
+    codename: bool,
+    /// Print the release version
+    #[clap(short = 'r', long = "release")]
+    release: bool,
+    /// Print the full name, such as `Debian 12 "Bookworm"`
+    #[clap(short = 'f', long = "fullname")]
+    fullname: bool,
+}
+
+/// The distributions this binary can mimic, by the name it is invoked under.
+pub const PROGRAMS: [(&str, Vendor); 2] = [
+    ("debian-distro-info", Vendor::Debian),
+    ("ubuntu-distro-info", Vendor::Ubuntu),
+];
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub enum Vendor {
+    Debian,
+    Ubuntu,
+}
+
+impl Vendor {
+    const fn origin(self) -> &'static str {
+        match self {
+            Self::Debian => "debian",
+            Self::Ubuntu => "ubuntu",
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Debian => "Debian",
+            Self::Ubuntu => "Ubuntu",
+        }
+    }
+
+    const fn about(self) -> &'static str {
+        match self {
+            Self::Debian => "Print the codenames or versions of Debian releases",
+            Self::Ubuntu => "Print the codenames or versions of Ubuntu releases",
+        }
+    }
+}
+
+impl Args {
+    fn select<'t>(
+        &self,
+        vendor: Vendor,
+        table: &'t DistroInfoTable,
+        date: NaiveDate,
+    ) -> Result<Vec<&'t DistroRelease>, String> {
+        let debian_only = |option: &str| {
+            if vendor == Vendor::Debian {
+                Ok(())
+            } else {
+                Err(format!("{option} is only available for Debian"))
+            }
+        };
+
+        let selected = if self.all {
+            table.releases().iter().collect()
+        } else if self.devel {
+            match vendor {
+                Vendor::Debian => table.by_alias("unstable", date),
+                Vendor::Ubuntu => table.testing(date),
+            }
+            .into_iter()
+            .collect()
+        } else if self.stable {
+            table.by_alias("stable", date).into_iter().collect()
+        } else if self.oldstable {
+            debian_only("--oldstable")?;
+            table.by_alias("oldstable", date).into_iter().collect()
+        } else if self.testing {
+            debian_only("--testing")?;
+            table.testing(date).into_iter().collect()
+        } else if self.lts {
+            match vendor {
+                Vendor::Debian => table
+                    .releases()
+                    .iter()
+                    .filter(|r| r.support_status(date).phase == SupportPhase::Lts)
+                    .collect(),
+                Vendor::Ubuntu => table
+                    .releases()
+                    .iter()
+                    .filter(|r| r.version.as_ref().is_some_and(|v| v.contains("LTS")))
+                    .filter(|r| r.release.is_some_and(|release| release <= date))
+                    .max_by_key(|r| r.release)
+                    .into_iter()
+                    .collect(),
+            }
+        } else if self.supported {
+            table.supported(date)
+        } else {
+            table.unsupported(date)
+        };
+
+        Ok(selected)
+    }
+
+    fn format(&self, vendor: Vendor, release: &DistroRelease) -> String {
+        let version = release.version.as_ref().unwrap_or(&release.series);
+
+        if self.release {
+            version.clone()
+        } else if self.fullname {
+            format!("{} {version} \"{}\"", vendor.name(), release.codename)
+        } else {
+            release.series.clone()
+        }
+    }
+}
+
+/// Runs as `program`, one of [`PROGRAMS`], and returns the exit status.
+pub fn run(program: &str, vendor: Vendor) -> i32 {
+    // the help names the program as it was invoked, rather than this binary
+    let matches = Args::command()
+        .name(program)
+        .about(vendor.about())
+        .long_about(None)
+        .get_matches();
+    let args = Args::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let date = args.date.unwrap_or_else(|| Local::now().date_naive());
+
+    let table = match DistroInfoTable::load(vendor.origin()) {
+        Ok(table) => table,
+        Err(e) => {
+            eprintln!("{program}: {e}");
+            return 1;
+        }
+    };
+
+    match args.select(vendor, &table, date) {
+        Ok(releases) if releases.is_empty() => {
+            // distro-info says the same when the data does not cover the date
+            eprintln!("{program}: Distribution data outdated.");
+            1
+        }
+        Ok(releases) => {
+            for release in releases {
+                println!("{}", args.format(vendor, release));
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{program}: {e}");
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::DEBIAN_CSV;
+
+    const UBUNTU_CSV: &str = "\
+version,codename,series,created,release,eol,eol-server,eol-esm
+20.04 LTS,Focal Fossa,focal,2019-10-17,2020-04-23,2025-05-29,2025-05-29,2030-04-23
+22.04 LTS,Jammy Jellyfish,jammy,2021-10-14,2022-04-21,2027-06-01,2027-06-01,2032-04-21
+23.10,Mantic Minotaur,mantic,2023-04-20,2023-10-12,2024-07-11
+24.04 LTS,Noble Numbat,noble,2023-10-12,2024-04-25,2029-05-31,2029-05-31,2034-04-25
+";
+
+    /// The series `option` selects from `csv` on `date`, or the error it gives.
+    fn select(vendor: Vendor, csv: &str, date: &str, option: &str) -> Result<Vec<String>, String> {
+        let table = DistroInfoTable::parse(csv.as_bytes()).unwrap();
+        let args = Args::try_parse_from(["distro-info", option]).unwrap();
+
+        args.select(vendor, &table, date.parse().unwrap())
+            .map(|releases| releases.iter().map(|r| r.series.clone()).collect())
+    }
+
+    #[test]
+    fn selects_debian_releases() {
+        let debian = |option| select(Vendor::Debian, DEBIAN_CSV, "2025-01-01", option);
+
+        assert_eq!(debian("--stable").unwrap(), ["bookworm"]);
+        assert_eq!(debian("--oldstable").unwrap(), ["bullseye"]);
+        assert_eq!(debian("--testing").unwrap(), ["trixie"]);
+        assert_eq!(debian("--devel").unwrap(), ["sid"]);
+        assert_eq!(debian("--lts").unwrap(), ["bullseye"]);
+        assert_eq!(
+            debian("--supported").unwrap(),
+            ["bookworm", "trixie", "sid"]
+        );
+    }
+
+    #[test]
+    fn selects_ubuntu_releases() {
+        let ubuntu = |option| select(Vendor::Ubuntu, UBUNTU_CSV, "2024-03-01", option);
+
+        assert_eq!(ubuntu("--stable").unwrap(), ["mantic"]);
+        assert_eq!(ubuntu("--devel").unwrap(), ["noble"]);
+        assert_eq!(ubuntu("--lts").unwrap(), ["jammy"]);
+        assert_eq!(
+            ubuntu("--supported").unwrap(),
+            ["focal", "jammy", "mantic", "noble"]
+        );
+        assert_eq!(
+            ubuntu("--oldstable").unwrap_err(),
+            "--oldstable is only available for Debian"
+        );
+        assert_eq!(
+            ubuntu("--testing").unwrap_err(),
+            "--testing is only available for Debian"
+        );
+    }
+}
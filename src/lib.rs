@@ -0,0 +1,69 @@
+//! This crate is ported version of `lsb_release(1)`.
+//!
+//! The `lsb-release-rs` binary is a thin front-end over this library; everything it prints can be
+//! obtained from [`grub_info`]:
+//!
+//! ```no_run
+//! use lsb_release_rs::{grub_info, LSBInfo};
+//!
+//! let info = grub_info();
+//! println!("{} {}", info.id().unwrap_or_default(), info.release().unwrap_or_default());
+//! ```
+//!
+//! [`DistroInfo`] and [`lsb_version`] give direct access to the underlying detection, and
+//! [`format`](mod@format) renders the same templates as `--format`. [`DistroInfoTable`] looks
+//! releases up by version, codename or alias, such as `bookworm` to `12`.
+
+#![deny(clippy::all, clippy::implicit_clone)]
+#![warn(
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::restriction,
+    clippy::cargo,
+    clippy::get_unwrap
+)]
+#![allow(
+    clippy::cargo_common_metadata,
+    clippy::blanket_clippy_restriction_lints,
+    clippy::missing_docs_in_private_items,
+    clippy::print_stderr,
+    clippy::print_stdout,
+    clippy::shadow_reuse,
+    clippy::implicit_return,
+    clippy::str_to_string,
+    clippy::indexing_slicing,
+    clippy::unwrap_used,
+    clippy::arithmetic_side_effects,
+    clippy::string_slice,
+    clippy::unwrap_in_result,
+    clippy::expect_used,
+    clippy::shadow_unrelated,
+    clippy::too_many_lines,
+    clippy::cast_possible_truncation,
+    clippy::default_numeric_fallback
+)]
+
+// so that `test_info` names this crate the same way from here and from the binary
+#[cfg(test)]
+extern crate self as lsb_release_rs;
+
+mod lsb_release;
+#[cfg(test)]
+mod test_info;
+
+pub use crate::lsb_release::error::LsbError;
+pub use crate::lsb_release::format;
+pub use crate::lsb_release::get::{
+    grub_info, grub_info_in, grub_info_with, upstream_grub_info, upstream_grub_info_in,
+    DetectionOptions, LSBInfo,
+};
+pub use crate::lsb_release::imp::deb_version::DebVersion;
+pub use crate::lsb_release::imp::dist::{
+    lsb_version, lsb_version_in, lsb_version_in_root, DistroInfo,
+};
+pub use crate::lsb_release::imp::distro_info::{
+    DistroInfoSource, DistroInfoTable, DistroRelease, SupportPhase, SupportStatus,
+};
+pub use crate::lsb_release::imp::lsb::LsbCompatTable;
+pub use crate::lsb_release::imp::lsb_module::LsbModule;
+pub use crate::lsb_release::imp::os_release::{OsRelease, OsReleaseSource};
@@ -1,2 +1,4 @@
+pub mod error;
+pub mod format;
 pub mod get;
-mod imp;
+pub mod imp;
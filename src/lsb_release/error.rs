@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+
+/// Why the distribution could not be detected.
+///
+/// Each variant names what was being read, either a file path or a command such as
+/// `apt-cache policy`, so the message can be shown to the user as is.
+#[derive(Debug)]
+#[non_exhaustive]
+#[allow(clippy::module_name_repetitions)]
+pub enum LsbError {
+    /// A file could not be read, or a command could not be run.
+    Io {
+        /// The path of the file or the name of the command.
+        context: String,
+        /// The error reading or running it.
+        source: io::Error,
+    },
+    /// A file or the output of a command is not in the expected format.
+    Parse {
+        /// What was parsed, such as a path or a value read from a file.
+        context: String,
+        /// What is wrong with it.
+        reason: String,
+    },
+    /// A program needed for detection is not installed.
+    MissingTool(&'static str),
+    /// A file or the output of a command is not valid UTF-8.
+    Encoding {
+        /// The path of the file or a description of the output.
+        context: String,
+    },
+    /// None of the sources describes the distribution.
+    NotDetected(&'static str),
+}
+
+impl LsbError {
+    /// Wraps an error from reading `path`; invalid UTF-8 is reported as [`LsbError::Encoding`].
+    pub(in crate::lsb_release) fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| Self::from_io(path.display().to_string(), source)
+    }
+
+    fn from_io(context: String, source: io::Error) -> Self {
+        if source.kind() == io::ErrorKind::InvalidData {
+            Self::Encoding { context }
+        } else {
+            Self::Io { context, source }
+        }
+    }
+
+    /// Wraps an error from running `tool`; a tool which is not installed is reported as
+    /// [`LsbError::MissingTool`].
+    pub(in crate::lsb_release) fn command(tool: &'static str) -> impl FnOnce(io::Error) -> Self {
+        move |source| {
+            if source.kind() == io::ErrorKind::NotFound {
+                Self::MissingTool(tool)
+            } else {
+                Self::Io {
+                    context: tool.to_string(),
+                    source,
+                }
+            }
+        }
+    }
+
+    /// Wraps an error from reading CSV data, described by `context` such as its path.
+    pub(in crate::lsb_release) fn csv(context: String) -> impl FnOnce(csv::Error) -> Self {
+        move |error| {
+            if matches!(error.kind(), csv::ErrorKind::Utf8 { .. }) {
+                return Self::Encoding { context };
+            }
+
+            let reason = error.to_string();
+            match error.into_kind() {
+                csv::ErrorKind::Io(source) => Self::from_io(context, source),
+                _ => Self::Parse { context, reason },
+            }
+        }
+    }
+}
+
+impl Display for LsbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io { context, source } => write!(f, "cannot read {context}: {source}"),
+            Self::Parse { context, reason } => write!(f, "cannot parse {context}: {reason}"),
+            Self::MissingTool(tool) => write!(f, "`{tool}` is not installed"),
+            Self::Encoding { context } => write!(f, "{context} is not valid UTF-8"),
+            Self::NotDetected(what) => write!(f, "{what} could not be detected"),
+        }
+    }
+}
+
+impl Error for LsbError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
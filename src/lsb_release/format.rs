@@ -0,0 +1,217 @@
+use crate::lsb_release::get::LSBInfo;
+use crate::lsb_release::imp::lsb_module::LsbModule;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A user-defined output template, such as `{id}-{release} ({codename:-unknown})`.
+///
+/// The syntax is:
+///
+/// - `{field}` is replaced by the value of `field`, or by nothing if it is not available.
+/// - `{field:-default}` is replaced by `default` if `field` is not available or empty.
+/// - `{{` and `}}` are a literal `{` and `}`.
+///
+/// See [`Field`] for the recognized field names.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+enum Segment {
+    Literal(String),
+    Field {
+        field: Field,
+        default: Option<String>,
+    },
+}
+
+/// The values a [`Template`] can refer to.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum Field {
+    /// `{id}`, same as `-i`.
+    Id,
+    /// `{description}`, same as `-d`.
+    Description,
+    /// `{release}`, same as `-r`.
+    Release,
+    /// `{codename}`, same as `-c`.
+    Codename,
+    /// `{lsb_modules}`, same as `-v`; modules are joined with `:`.
+    LsbModules,
+}
+
+impl FromStr for Field {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "id" => Ok(Self::Id),
+            "description" => Ok(Self::Description),
+            "release" => Ok(Self::Release),
+            "codename" => Ok(Self::Codename),
+            "lsb_modules" => Ok(Self::LsbModules),
+            _ => Err(TemplateError::UnknownField(s.to_string())),
+        }
+    }
+}
+
+impl Field {
+    fn value(self, info: &impl LSBInfo) -> Option<String> {
+        match self {
+            Self::Id => info.id(),
+            Self::Description => info.description(),
+            Self::Release => info.release(),
+            Self::Codename => info.codename(),
+            Self::LsbModules => info.lsb_version().as_deref().map(LsbModule::join),
+        }
+    }
+}
+
+/// Why a [`Template`] could not be parsed.
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub enum TemplateError {
+    /// A `{` at the given byte offset is never closed.
+    UnclosedBrace(usize),
+    /// A `}` at the given byte offset has no matching `{`; write `}}` for a literal one.
+    UnmatchedClosingBrace(usize),
+    /// The placeholder names a field that does not exist.
+    UnknownField(String),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnclosedBrace(at) => write!(f, "`{{` at offset {at} is never closed"),
+            Self::UnmatchedClosingBrace(at) => {
+                write!(f, "`}}` at offset {at} has no matching `{{` (use `}}}}` for a literal one)")
+            }
+            Self::UnknownField(name) => write!(
+                f,
+                "unknown field `{name}` (expected one of id, description, release, codename, lsb_modules)"
+            ),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = vec![];
+        let mut literal = String::new();
+        let mut chars = s.char_indices().peekable();
+
+        while let Some((at, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, next)| *next == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|(_, next)| *next == '}').is_some() => literal.push('}'),
+                '}' => return Err(TemplateError::UnmatchedClosingBrace(at)),
+                '{' => {
+                    let body = s[at + 1..]
+                        .split_once('}')
+                        .map(|(body, _)| body)
+                        .ok_or(TemplateError::UnclosedBrace(at))?;
+                    // skip the body and the closing brace
+                    for _ in 0..=body.chars().count() {
+                        chars.next();
+                    }
+
+                    let (name, default) = body
+                        .split_once(":-")
+                        .map_or((body, None), |(name, default)| {
+                            (name, Some(default.to_string()))
+                        });
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field {
+                        field: name.trim().parse()?,
+                        default,
+                    });
+                }
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+}
+
+impl Template {
+    /// Renders this template with the values from `info`.
+    pub fn render(&self, info: &impl LSBInfo) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.clone(),
+                Segment::Field { field, default } => field
+                    .value(info)
+                    .filter(|value| !value.is_empty())
+                    .or_else(|| default.clone())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::FixedInfo;
+
+    fn render(template: &str) -> String {
+        let info = FixedInfo {
+            description: Some(String::new()),
+            codename: None,
+            ..FixedInfo::bookworm()
+        };
+
+        template.parse::<Template>().unwrap().render(&info)
+    }
+
+    #[test]
+    fn renders_fields_and_defaults() {
+        assert_eq!(render("{id}-{release}"), "Debian-12");
+        assert_eq!(render("{id} ({codename:-unknown})"), "Debian (unknown)");
+        assert_eq!(render("[{codename}]"), "[]");
+        assert_eq!(render("{description:-none}"), "none");
+        assert_eq!(render("{ release }"), "12");
+        assert_eq!(render("{lsb_modules}"), "core-4.1-amd64:core-4.1-noarch");
+    }
+
+    #[test]
+    fn renders_literals() {
+        assert_eq!(render(""), "");
+        assert_eq!(render("plain text"), "plain text");
+        assert_eq!(render("{{{id}}}"), "{Debian}");
+        assert_eq!(render("é{id}ü"), "éDebianü");
+        assert_eq!(render("{codename:-a:-b}"), "a:-b");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert_eq!(
+            "{id".parse::<Template>(),
+            Err(TemplateError::UnclosedBrace(0))
+        );
+        assert_eq!(
+            "id}".parse::<Template>(),
+            Err(TemplateError::UnmatchedClosingBrace(2))
+        );
+        assert_eq!(
+            "{name}".parse::<Template>(),
+            Err(TemplateError::UnknownField("name".to_string()))
+        );
+    }
+}
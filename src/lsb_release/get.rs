@@ -1,49 +1,249 @@
-use crate::lsb_release::imp::dist::{lsb_version, DistroInfo};
+use crate::lsb_release::error::LsbError;
+use crate::lsb_release::imp::dist::{lsb_version_with_compat, DistroInfo};
+use crate::lsb_release::imp::distro_info::{DistroInfoSource, DistroInfoTable, DistroRelease};
+use crate::lsb_release::imp::lsb::LsbCompatTable;
+use crate::lsb_release::imp::lsb_module::LsbModule;
+use crate::lsb_release::imp::os_release::OsReleaseSource;
+use crate::lsb_release::imp::paths::PathGetter;
+use chrono::{Local, NaiveDate};
+use once_cell::unsync::OnceCell;
+use std::path::PathBuf;
 
+/// The questions `lsb_release` answers. Every method returns `None` if the value is unknown.
 pub trait LSBInfo {
+    /// Distributor ID, as printed by `-i`.
     fn id(&self) -> Option<String>;
 
+    /// Description of the distribution, as printed by `-d`.
     fn description(&self) -> Option<String>;
 
+    /// Release number, as printed by `-r`.
     fn release(&self) -> Option<String>;
 
+    /// Release codename, as printed by `-c`.
     fn codename(&self) -> Option<String>;
 
-    fn lsb_version(&self) -> Option<Vec<String>>;
+    /// Available LSB modules, as printed by `-v`.
+    fn lsb_version(&self) -> Option<Vec<LsbModule>>;
+
+    /// The distro-info entry of the release, which tells its support dates, as used by `--eol` and
+    /// `--support-status`.
+    fn distro_release(&self) -> Option<DistroRelease> {
+        None
+    }
+
+    /// The whole distro-info table of the distribution, as used by `--ical`.
+    fn distro_info_table(&self) -> Option<DistroInfoTable> {
+        None
+    }
+
+    /// Codename of the Debian testing release, such as `trixie`, as printed by
+    /// `--testing-codename`. It is the current one unless [`DetectionOptions::date`] says
+    /// otherwise. It is taken from the Debian distro-info table, whichever distribution is running.
+    fn testing_codename(&self) -> Option<String> {
+        None
+    }
+
+    /// Explanations of surprising detection results, such as an ignored file, as printed by
+    /// `--verbose`.
+    fn diagnostics(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Runs each detection at most once, on first use, and shares the result between the accessors.
+#[derive(Default)]
+struct LSBInfoGetter {
+    paths: PathGetter,
+    upstream: bool,
+    date: Option<NaiveDate>,
+    distro_information: OnceCell<Result<DistroInfo, LsbError>>,
+    lsb_version: OnceCell<Result<Option<Vec<LsbModule>>, LsbError>>,
+    distro_info_table: OnceCell<Option<DistroInfoTable>>,
+    os_release_source: OnceCell<OsReleaseSource>,
+    /// The module table, and why the one of the system was ignored if it was.
+    compat_table: OnceCell<(LsbCompatTable, Option<LsbError>)>,
 }
 
-struct LSBInfoGetter;
+impl LSBInfoGetter {
+    fn detect(&self) -> &Result<DistroInfo, LsbError> {
+        self.distro_information.get_or_init(|| {
+            if self.upstream {
+                DistroInfo::detect_upstream(&self.paths)
+            } else {
+                DistroInfo::detect(&self.paths)
+            }
+        })
+    }
+
+    fn modules(&self) -> &Result<Option<Vec<LsbModule>>, LsbError> {
+        self.lsb_version
+            .get_or_init(|| lsb_version_with_compat(&self.paths, &self.compat_table().0))
+    }
+
+    fn os_release_source(&self) -> &OsReleaseSource {
+        self.os_release_source
+            .get_or_init(|| self.paths.os_release_source())
+    }
+
+    fn compat_table(&self) -> &(LsbCompatTable, Option<LsbError>) {
+        self.compat_table
+            .get_or_init(|| LsbCompatTable::load_checked(&self.paths))
+    }
+
+    fn distro_information(&self) -> Option<&DistroInfo> {
+        self.detect().as_ref().ok()
+    }
+
+    fn table(&self) -> Option<&DistroInfoTable> {
+        self.distro_info_table
+            .get_or_init(|| {
+                self.distro_information()
+                    .and_then(|info| info.distro_info_table(&self.paths))
+            })
+            .as_ref()
+    }
+}
 
 // replacement for /usr/share/pyshared/lsb_release.py
 impl LSBInfo for LSBInfoGetter {
     fn id(&self) -> Option<String> {
-        DistroInfo::get_distro_information().ok().and_then(|a| a.id)
+        self.distro_information().and_then(|a| a.id.clone())
     }
 
     fn description(&self) -> Option<String> {
-        DistroInfo::get_distro_information()
-            .ok()
-            .and_then(|a| a.description)
+        self.distro_information()
+            .and_then(|a| a.description.clone())
     }
 
     fn release(&self) -> Option<String> {
-        DistroInfo::get_distro_information()
-            .ok()
-            .and_then(|a| a.release)
+        self.distro_information().and_then(|a| a.release.clone())
     }
 
     fn codename(&self) -> Option<String> {
-        DistroInfo::get_distro_information()
-            .ok()
-            .and_then(|a| a.codename)
+        self.distro_information().and_then(|a| a.codename.clone())
     }
 
     // this is check_modules_installed()
-    fn lsb_version(&self) -> Option<Vec<String>> {
-        lsb_version()
+    fn lsb_version(&self) -> Option<Vec<LsbModule>> {
+        self.modules().as_ref().ok().cloned().flatten()
+    }
+
+    fn distro_release(&self) -> Option<DistroRelease> {
+        self.distro_information()?.find_in(self.table()?).cloned()
+    }
+
+    fn distro_info_table(&self) -> Option<DistroInfoTable> {
+        self.table().cloned()
+    }
+
+    fn testing_codename(&self) -> Option<String> {
+        let table = DistroInfoTable::load_with(&self.paths, "debian").ok()?;
+
+        table
+            .testing(self.date.unwrap_or_else(|| Local::now().date_naive()))
+            .map(|testing| testing.series.clone())
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        let os_release = self.os_release_source();
+        let broken_compat_table = self
+            .compat_table()
+            .1
+            .as_ref()
+            .map(|e| format!("the LSB module table is ignored: {e}"));
+
+        os_release
+            .shadowed
+            .is_some()
+            .then(|| os_release.to_string())
+            .into_iter()
+            .chain(self.detect().as_ref().err().map(ToString::to_string))
+            .chain(
+                self.modules()
+                    .as_ref()
+                    .err()
+                    .map(|e| format!("the LSB modules could not be listed: {e}")),
+            )
+            .chain(broken_compat_table)
+            .collect()
     }
 }
 
+/// Returns an [`LSBInfo`] for the running system.
+///
+/// Nothing is detected until the first accessor is called. The distribution is then detected
+/// once and the snapshot is shared by [`LSBInfo::id`], [`LSBInfo::description`],
+/// [`LSBInfo::release`] and [`LSBInfo::codename`]; the LSB modules are likewise looked up once.
+#[must_use]
 pub fn grub_info() -> impl LSBInfo {
-    LSBInfoGetter
+    grub_info_in("/")
+}
+
+/// Same as [`grub_info`], but for the system installed in `root`, such as a chroot or a mounted
+/// image.
+///
+/// The `LSB_*` environment variables name files of the running system, so they are ignored unless
+/// `root` is `/`.
+#[must_use]
+pub fn grub_info_in(root: impl Into<PathBuf>) -> impl LSBInfo {
+    grub_info_with(&DetectionOptions {
+        root: root.into(),
+        ..DetectionOptions::default()
+    })
+}
+
+/// Same as [`grub_info`], but describes the distribution the running one is derived from. See
+/// [`DistroInfo::get_upstream_distro_information`].
+#[must_use]
+pub fn upstream_grub_info() -> impl LSBInfo {
+    upstream_grub_info_in("/")
+}
+
+/// Same as [`upstream_grub_info`], but for the system installed in `root`.
+#[must_use]
+pub fn upstream_grub_info_in(root: impl Into<PathBuf>) -> impl LSBInfo {
+    grub_info_with(&DetectionOptions {
+        root: root.into(),
+        upstream: true,
+        ..DetectionOptions::default()
+    })
+}
+
+/// Settings of [`grub_info_with`]. The defaults are those of [`grub_info`].
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub struct DetectionOptions {
+    /// The system to inspect, as in [`grub_info_in`].
+    pub root: PathBuf,
+    /// Whether to describe the upstream distribution, as in [`upstream_grub_info`].
+    pub upstream: bool,
+    /// Where distro-info tables are read from.
+    pub distro_info_source: DistroInfoSource,
+    /// The date questions about time, such as which release is in testing, are answered for;
+    /// `None` means today. The installed system is detected as it is regardless.
+    pub date: Option<NaiveDate>,
+}
+
+impl Default for DetectionOptions {
+    fn default() -> Self {
+        Self {
+            root: PathBuf::from("/"),
+            upstream: false,
+            distro_info_source: DistroInfoSource::default(),
+            date: None,
+        }
+    }
+}
+
+/// Same as [`grub_info`], with every setting spelled out.
+#[must_use]
+pub fn grub_info_with(options: &DetectionOptions) -> impl LSBInfo {
+    LSBInfoGetter {
+        paths: PathGetter::new(options.root.as_path())
+            .with_distro_info_source(options.distro_info_source),
+        upstream: options.upstream,
+        date: options.date,
+        ..LSBInfoGetter::default()
+    }
 }
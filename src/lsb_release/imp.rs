@@ -1,3 +1,14 @@
 pub(in crate::lsb_release) mod apt;
-pub(in crate::lsb_release) mod dist;
-mod lsb;
+mod deb822;
+pub mod deb_version;
+pub mod dist;
+pub mod distro_info;
+mod dpkg;
+pub(in crate::lsb_release) mod etc_lsb_release;
+pub mod lsb;
+pub mod lsb_module;
+pub mod os_release;
+pub(in crate::lsb_release) mod paths;
+mod rpm;
+#[cfg(test)]
+pub(in crate::lsb_release) mod test_root;
@@ -1,12 +1,21 @@
+mod preferences;
+mod release_file;
+
+use crate::lsb_release::error::LsbError;
+use crate::lsb_release::imp::apt::preferences::Preferences;
+use crate::lsb_release::imp::apt::release_file::read_release_files;
+use crate::lsb_release::imp::paths::PathGetter;
 use fancy_regex::Regex;
-use std::env::var;
-use std::error::Error;
+use once_cell::sync::Lazy;
+use std::convert::Infallible;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::str::FromStr;
 
+static PRIORITY_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(-?\d+)"#).unwrap());
+
 #[derive(Eq, PartialEq, Clone)]
 pub(in crate::lsb_release::imp) struct AptCachePolicyEntry {
     pub(in crate::lsb_release::imp) priority: i64,
@@ -18,12 +27,13 @@ pub(in crate::lsb_release::imp) struct AptPolicy {
     pub(in crate::lsb_release::imp) version: Option<String>,
     pub(in crate::lsb_release::imp) origin: Option<String>,
     pub(in crate::lsb_release::imp) suite: Option<String>,
+    pub(in crate::lsb_release::imp) codename: Option<String>,
     pub(in crate::lsb_release::imp) component: Option<String>,
     pub(in crate::lsb_release::imp) label: Option<String>,
 }
 
 impl FromStr for AptPolicy {
-    type Err = ();
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut ret = Self::default();
@@ -42,6 +52,9 @@ impl FromStr for AptPolicy {
                     "a" => {
                         ret.suite = Some(v.to_string());
                     }
+                    "n" => {
+                        ret.codename = Some(v.to_string());
+                    }
                     "c" => {
                         ret.component = Some(v.to_string());
                     }
@@ -57,61 +70,160 @@ impl FromStr for AptPolicy {
     }
 }
 
-pub(in crate::lsb_release::imp) fn dpkg_default_vendor() -> Result<Option<String>, Box<dyn Error>> {
-    let f = File::open(dpkg_origin())?;
-    let f = BufReader::new(f);
-    Ok(f.lines()
-        .map(Result::unwrap)
-        .map(|line| {
-            line.splitn(2, ": ")
-                .map(std::string::ToString::to_string)
+pub(in crate::lsb_release::imp) fn dpkg_default_vendor(
+    paths: &PathGetter,
+) -> Result<Option<String>, LsbError> {
+    let path = paths.dpkg_origin();
+    let f = File::open(&path).map_err(LsbError::io(&path))?;
+    let mut vendor = None;
+    for line in BufReader::new(f).lines() {
+        let line = line.map_err(LsbError::io(&path))?;
+        // lines which are not `Header: content` are skipped, as in the Python implementation
+        if let Some((header, content)) = line.split_once(": ") {
+            if header.to_lowercase() == "vendor" {
+                vendor = Some(content.trim().to_string());
+            }
+        }
+    }
+
+    Ok(vendor)
+}
+
+/// Lists the package files APT knows about, with their priorities, like `apt-cache policy` does.
+///
+/// The release files in the APT lists directory are read directly. `apt-cache policy` is only
+/// run if there are none, e.g. because APT uses a non-default lists directory.
+pub(in crate::lsb_release::imp) fn parse_apt_policy(
+    paths: &PathGetter,
+) -> Result<Vec<AptCachePolicyEntry>, LsbError> {
+    match read_apt_lists(paths) {
+        Ok(entries) if !entries.is_empty() => Ok(entries),
+        _ => run_apt_cache_policy(paths),
+    }
+}
+
+fn read_apt_lists(paths: &PathGetter) -> Result<Vec<AptCachePolicyEntry>, LsbError> {
+    let preferences = Preferences::load(
+        paths.apt_preferences().as_path(),
+        paths.apt_preferences_parts().as_path(),
+    );
+
+    let lists = paths.apt_lists();
+    let entries = read_release_files(lists.as_path())
+        .map_err(LsbError::io(&lists))?
+        .into_iter()
+        .flat_map(|release_file| {
+            let priority = |policy: &AptPolicy| {
+                preferences
+                    .priority_of(release_file.host.as_str(), policy)
+                    .unwrap_or(release_file.default_priority)
+            };
+
+            release_file
+                .components
+                .iter()
+                .map(|component| {
+                    let policy = AptPolicy {
+                        component: component.clone(),
+                        ..release_file.policy.clone()
+                    };
+
+                    AptCachePolicyEntry {
+                        priority: priority(&policy),
+                        policy,
+                    }
+                })
                 .collect::<Vec<_>>()
         })
-        .map(|elements| (elements[0].clone(), elements[1].clone()))
-        .map(|(header, content)| (header.to_lowercase(), content.trim().to_string()))
-        .filter(|(header, _)| header == "vendor")
-        .last()
-        .map(|(_, content)| content))
-}
+        .collect();
 
-fn dpkg_origin() -> impl AsRef<Path> {
-    var("LSB_ETC_DPKG_ORIGINS_DEFAULT").unwrap_or_else(|_| "/etc/dpkg/origins/default".to_string())
+    Ok(entries)
 }
 
-pub(in crate::lsb_release::imp) fn parse_apt_policy(
-) -> Result<Vec<AptCachePolicyEntry>, Box<dyn Error>> {
-    let apt_cache_policy_output = Command::new("apt-cache")
+fn run_apt_cache_policy(paths: &PathGetter) -> Result<Vec<AptCachePolicyEntry>, LsbError> {
+    let mut apt_cache = Command::new("apt-cache");
+    if paths.root() != Path::new("/") {
+        // APT resolves all of its other directories relative to this one
+        apt_cache
+            .arg("-o")
+            .arg(format!("Dir={}", paths.root().display()));
+    }
+
+    let apt_cache_policy_output = apt_cache
         .arg("policy")
         // Command::new inherits env vars, so we need to just overwrite single variable
         .env("LC_ALL", "C.UTF-8")
-        .spawn()?
-        .wait_with_output()?;
-
-    // SAFETY: this shall be UTF-8
+        .stderr(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .and_then(std::process::Child::wait_with_output)
+        .map_err(LsbError::command("apt-cache"))?;
 
-    let regex = Regex::new(r#"(-?\d+)"#).unwrap();
+    let stdout =
+        String::from_utf8(apt_cache_policy_output.stdout).map_err(|_| LsbError::Encoding {
+            context: "`apt-cache policy` output".to_string(),
+        })?;
 
-    let data = String::from_utf8(apt_cache_policy_output.stdout)
-        .expect("This byte sequence is not valid UTF-8")
+    // the priority is on the line of the package file, and applies to the `release` lines below it
+    let mut priority = 0;
+    let data = stdout
         .lines()
         .map(str::trim)
-        .filter(|line| line.starts_with("release"))
-        .map(|line| {
-            let priority = regex
-                .captures(line)
-                .unwrap()
-                .map(|c| c[1].parse().unwrap())
-                .unwrap();
-            let bits = line.splitn(2, ' ').collect::<Vec<_>>();
+        .filter_map(|line| {
+            if let Some(captures) = PRIORITY_REGEX.captures(line).ok().flatten() {
+                priority = captures[1].parse().unwrap_or(priority);
+            }
 
-            (priority, bits)
-        })
-        .filter(|(_, bits)| bits.len() > 1)
-        .map(|(priority, bits)| AptCachePolicyEntry {
-            priority,
-            policy: bits[1].parse().unwrap(),
+            line.strip_prefix("release ")
+                .and_then(|policy| policy.parse().ok())
+                .map(|policy| AptCachePolicyEntry { priority, policy })
         })
         .collect();
 
     Ok(data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    #[test]
+    fn parses_release_lines_of_apt_cache_policy() {
+        let policy =
+            AptPolicy::from_str("v=12.5,o=Debian,a=stable,n=bookworm,l=Debian,c=main,b=amd64")
+                .unwrap();
+
+        assert_eq!(policy.version.as_deref(), Some("12.5"));
+        assert_eq!(policy.origin.as_deref(), Some("Debian"));
+        assert_eq!(policy.suite.as_deref(), Some("stable"));
+        assert_eq!(policy.codename.as_deref(), Some("bookworm"));
+        assert_eq!(policy.label.as_deref(), Some("Debian"));
+        assert_eq!(policy.component.as_deref(), Some("main"));
+        assert!(AptPolicy::from_str("release").unwrap() == AptPolicy::default());
+    }
+
+    #[test]
+    fn applies_pins_to_the_lists() {
+        let root = TestRoot::new()
+            .with(
+                "var/lib/apt/lists/deb.debian.org_debian_dists_trixie_Release",
+                "Origin: Debian\nSuite: testing\nCodename: trixie\nComponents: main contrib\n",
+            )
+            .with(
+                "var/lib/apt/lists/deb.debian.org_debian_dists_trixie_main_binary-amd64_Packages",
+                "",
+            )
+            .with(
+                "etc/apt/preferences.d/testing",
+                "Package: *\nPin: release a=testing\nPin-Priority: 990\n",
+            );
+
+        let entries = read_apt_lists(&PathGetter::new(root.path())).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].priority, 990);
+        assert_eq!(entries[0].policy.codename.as_deref(), Some("trixie"));
+        assert_eq!(entries[0].policy.component.as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::lsb_release::imp::apt::AptPolicy;
+use crate::lsb_release::imp::deb822::parse_paragraphs;
+use fancy_regex::Regex;
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+
+/// The pins from `/etc/apt/preferences` and `/etc/apt/preferences.d` which apply to whole package
+/// files, i.e. those with `Package: *` and a `release` or `origin` pin.
+///
+/// Pins for specific packages and `version` pins do not change the priority `apt-cache policy`
+/// shows for a package file, so they are skipped.
+#[derive(Default)]
+pub(in crate::lsb_release::imp::apt) struct Preferences {
+    pins: Vec<Pin>,
+}
+
+struct Pin {
+    selector: PinSelector,
+    priority: i64,
+}
+
+enum PinSelector {
+    /// `Pin: release a=stable, n=bookworm`
+    Release(Vec<(String, String)>),
+    /// `Pin: origin deb.debian.org`
+    Origin(String),
+}
+
+impl Preferences {
+    /// Reads `file` first and then every file in `dir` in lexical order, as APT does.
+    ///
+    /// Missing or unreadable files are skipped, like APT does for the default locations.
+    pub(in crate::lsb_release::imp::apt) fn load(file: &Path, dir: &Path) -> Self {
+        let mut paths = vec![file.to_path_buf()];
+
+        if let Ok(entries) = read_dir(dir) {
+            let mut parts = entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(is_valid_part_name)
+                })
+                .collect::<Vec<_>>();
+            parts.sort();
+            paths.append(&mut parts);
+        }
+
+        let pins = paths
+            .iter()
+            .filter_map(|path| read_to_string(path).ok())
+            .flat_map(|text| parse_paragraphs(text.as_str()))
+            .filter(|paragraph| paragraph.get("Package") == Some("*"))
+            .filter_map(|paragraph| {
+                let priority = paragraph.get("Pin-Priority")?.parse().ok()?;
+                let (kind, spec) = paragraph.get("Pin")?.split_once(' ')?;
+                let selector = match kind {
+                    "release" => PinSelector::Release(parse_release_spec(spec)),
+                    "origin" => PinSelector::Origin(spec.trim().trim_matches('"').to_string()),
+                    _ => return None,
+                };
+
+                Some(Pin { selector, priority })
+            })
+            .collect();
+
+        Self { pins }
+    }
+
+    /// Returns the priority of the first pin matching a package file, if any.
+    ///
+    /// `host` is the host name the package file was downloaded from.
+    pub(in crate::lsb_release::imp::apt) fn priority_of(
+        &self,
+        host: &str,
+        policy: &AptPolicy,
+    ) -> Option<i64> {
+        self.pins
+            .iter()
+            .find(|pin| pin.selector.matches(host, policy))
+            .map(|pin| pin.priority)
+    }
+}
+
+impl PinSelector {
+    fn matches(&self, host: &str, policy: &AptPolicy) -> bool {
+        match self {
+            Self::Origin(origin) => origin == host,
+            Self::Release(conditions) => conditions.iter().all(|(key, pattern)| {
+                let value = match key.as_str() {
+                    "v" => &policy.version,
+                    "o" => &policy.origin,
+                    "a" => &policy.suite,
+                    "n" => &policy.codename,
+                    "c" => &policy.component,
+                    "l" => &policy.label,
+                    // e.g. `b=`, which we don't track
+                    _ => return false,
+                };
+
+                value
+                    .as_ref()
+                    .is_some_and(|value| pattern_matches(pattern, value))
+            }),
+        }
+    }
+}
+
+/// Parses `a=stable, n=bookworm`. A bare value, such as `release 12`, is a version.
+fn parse_release_spec(spec: &str) -> Vec<(String, String)> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|condition| !condition.is_empty())
+        .map(|condition| {
+            condition.split_once('=').map_or_else(
+                || ("v".to_string(), condition.to_string()),
+                |(key, value)| (key.trim().to_string(), value.trim().to_string()),
+            )
+        })
+        .collect()
+}
+
+/// APT accepts a `/regex/` or a glob with `*` and `?` wherever a pin compares a value.
+fn pattern_matches(pattern: &str, value: &str) -> bool {
+    if let Some(regex) = pattern.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+        return Regex::new(regex)
+            .ok()
+            .and_then(|regex| regex.is_match(value).ok())
+            .unwrap_or(false);
+    }
+
+    glob_matches(
+        pattern.chars().collect::<Vec<_>>().as_slice(),
+        value.chars().collect::<Vec<_>>().as_slice(),
+    )
+}
+
+fn glob_matches(pattern: &[char], value: &[char]) -> bool {
+    match (pattern.first(), value.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            glob_matches(&pattern[1..], value)
+                || (!value.is_empty() && glob_matches(pattern, &value[1..]))
+        }
+        (Some('?'), Some(_)) => glob_matches(&pattern[1..], &value[1..]),
+        (Some(p), Some(v)) if p == v => glob_matches(&pattern[1..], &value[1..]),
+        _ => false,
+    }
+}
+
+/// APT silently ignores files in `preferences.d` whose name has characters other than
+/// `A-Za-z0-9_.-`, or which have an extension other than `.pref`.
+fn is_valid_part_name(name: &str) -> bool {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || ['_', '.', '-'].contains(&c));
+    let valid_extension = Path::new(name)
+        .extension()
+        .is_none_or(|extension| extension == "pref");
+
+    valid_chars && valid_extension
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    fn policy(suite: &str, codename: &str) -> AptPolicy {
+        AptPolicy {
+            origin: Some("Debian".to_string()),
+            suite: Some(suite.to_string()),
+            codename: Some(codename.to_string()),
+            component: Some("main".to_string()),
+            ..AptPolicy::default()
+        }
+    }
+
+    #[test]
+    fn matches_globs_and_regexes() {
+        assert!(pattern_matches("bookworm", "bookworm"));
+        assert!(pattern_matches("book*", "bookworm"));
+        assert!(pattern_matches("*-security", "bookworm-security"));
+        assert!(pattern_matches("bookw?rm", "bookworm"));
+        assert!(pattern_matches("/^book.*m$/", "bookworm"));
+        assert!(!pattern_matches("book", "bookworm"));
+        assert!(!pattern_matches("/^trixie/", "bookworm"));
+        assert!(!pattern_matches("/[/", "bookworm"));
+    }
+
+    #[test]
+    fn parses_release_specs() {
+        assert_eq!(
+            parse_release_spec("a=stable, n=bookworm,"),
+            [
+                ("a".to_string(), "stable".to_string()),
+                ("n".to_string(), "bookworm".to_string())
+            ]
+        );
+        assert_eq!(
+            parse_release_spec("12"),
+            [("v".to_string(), "12".to_string())]
+        );
+    }
+
+    #[test]
+    fn accepts_the_part_names_apt_accepts() {
+        assert!(is_valid_part_name("backports"));
+        assert!(is_valid_part_name("00-local.pref"));
+        assert!(!is_valid_part_name("local.pref.bak"));
+        assert!(!is_valid_part_name("local pins"));
+    }
+
+    #[test]
+    fn first_matching_pin_wins() {
+        let root = TestRoot::new()
+            .with(
+                "etc/apt/preferences",
+                "Package: *\nPin: release n=trixie\nPin-Priority: 100\n\n\
+                 Package: bash\nPin: release n=bookworm\nPin-Priority: 1001\n",
+            )
+            .with(
+                "etc/apt/preferences.d/10-debian.pref",
+                "Package: *\nPin: release o=Debian, a=stable\nPin-Priority: 900\n\n\
+                 Package: *\nPin: origin \"deb.debian.org\"\nPin-Priority: 600\n",
+            )
+            .with(
+                "etc/apt/preferences.d/ignored.txt",
+                "Package: *\nPin: release a=*\nPin-Priority: -1\n",
+            );
+        let preferences = Preferences::load(
+            root.path().join("etc/apt/preferences").as_path(),
+            root.path().join("etc/apt/preferences.d").as_path(),
+        );
+
+        assert_eq!(
+            preferences.priority_of("x", &policy("testing", "trixie")),
+            Some(100)
+        );
+        assert_eq!(
+            preferences.priority_of("x", &policy("stable", "bookworm")),
+            Some(900)
+        );
+        assert_eq!(
+            preferences.priority_of("deb.debian.org", &policy("oldstable", "bullseye")),
+            Some(600)
+        );
+        assert_eq!(
+            preferences.priority_of("x", &policy("oldstable", "bullseye")),
+            None
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::lsb_release::imp::apt::AptPolicy;
+use crate::lsb_release::imp::deb822::parse_paragraphs;
+use std::collections::BTreeSet;
+use std::fs::{read_dir, read_to_string};
+use std::io;
+use std::path::Path;
+
+/// A `Release` or `InRelease` file in the APT lists directory, which describes one repository.
+pub(in crate::lsb_release::imp::apt) struct ReleaseFile {
+    /// The host name the repository was downloaded from, e.g. `deb.debian.org`.
+    pub(in crate::lsb_release::imp::apt) host: String,
+    /// Everything but the component, which is listed separately.
+    pub(in crate::lsb_release::imp::apt) policy: AptPolicy,
+    /// Components which have downloaded package indices; `None` stands for a flat repository.
+    pub(in crate::lsb_release::imp::apt) components: Vec<Option<String>>,
+    /// Priority before pinning: 500, or less if the repository is `NotAutomatic`.
+    pub(in crate::lsb_release::imp::apt) default_priority: i64,
+}
+
+/// Reads every release file in `lists`, such as `/var/lib/apt/lists`.
+///
+/// When both `InRelease` and `Release` exist for a repository, `InRelease` is used, as APT does.
+pub(in crate::lsb_release::imp::apt) fn read_release_files(
+    lists: &Path,
+) -> io::Result<Vec<ReleaseFile>> {
+    let names = read_dir(lists)?
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<BTreeSet<_>>();
+
+    let release_files = names
+        .iter()
+        .filter_map(|name| {
+            // the prefix keeps its trailing `_`, so that indices are `{prefix}{component}_...`
+            let in_release = name.strip_suffix("InRelease");
+            let release = name
+                .strip_suffix("Release")
+                .filter(|prefix| !names.contains(&format!("{prefix}InRelease")));
+
+            in_release
+                .or(release)
+                .filter(|prefix| prefix.ends_with('_'))
+                .map(|prefix| (prefix, name))
+        })
+        .filter_map(|(prefix, name)| {
+            let text = read_to_string(lists.join(name)).ok()?;
+            let paragraph = parse_paragraphs(strip_signature(text.as_str()).as_str())
+                .into_iter()
+                .next()?;
+
+            let policy = AptPolicy {
+                version: paragraph.get("Version").map(ToString::to_string),
+                origin: paragraph.get("Origin").map(ToString::to_string),
+                suite: paragraph.get("Suite").map(ToString::to_string),
+                codename: paragraph.get("Codename").map(ToString::to_string),
+                component: None,
+                label: paragraph.get("Label").map(ToString::to_string),
+            };
+
+            let has_indices =
+                |index_prefix: &str| names.iter().any(|n| n.starts_with(index_prefix));
+            let components = paragraph.get("Components").map_or_else(
+                || {
+                    if has_indices(format!("{prefix}Packages").as_str()) {
+                        vec![None]
+                    } else {
+                        vec![]
+                    }
+                },
+                |components| {
+                    components
+                        .split_whitespace()
+                        .filter_map(|component| {
+                            // Debian-Security lists `updates/main`, but APT fetches it as `main`
+                            let short = component.rsplit('/').next().unwrap_or(component);
+                            [component, short].into_iter().find(|candidate| {
+                                let escaped = candidate.replace('/', "_");
+                                has_indices(format!("{prefix}{escaped}_binary-").as_str())
+                            })
+                        })
+                        .map(|component| Some(component.to_string()))
+                        .collect()
+                },
+            );
+
+            let is_yes = |field: &str| paragraph.get(field) == Some("yes");
+            let default_priority = match (is_yes("NotAutomatic"), is_yes("ButAutomaticUpgrades")) {
+                (true, true) => 100,
+                (true, false) => 1,
+                _ => 500,
+            };
+
+            Some(ReleaseFile {
+                host: prefix.split('_').next().unwrap_or_default().to_string(),
+                policy,
+                components,
+                default_priority,
+            })
+        })
+        .collect();
+
+    Ok(release_files)
+}
+
+/// Returns the signed text of a clear-signed `InRelease` file, or `text` itself if it is not
+/// signed.
+fn strip_signature(text: &str) -> String {
+    if !text.starts_with("-----BEGIN PGP SIGNED MESSAGE-----") {
+        return text.to_string();
+    }
+
+    text.lines()
+        .skip(1)
+        // armor headers, such as `Hash: SHA256`, end with a blank line
+        .skip_while(|line| !line.trim().is_empty())
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----BEGIN PGP SIGNATURE-----"))
+        .map(|line| line.strip_prefix("- ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    const RELEASE: &str = "Origin: Debian\nLabel: Debian\nSuite: stable\nVersion: 12.5\n\
+                           Codename: bookworm\nComponents: main contrib non-free-firmware\n";
+
+    #[test]
+    fn strips_clear_signatures() {
+        let signed = format!(
+            "-----BEGIN PGP SIGNED MESSAGE-----\nHash: SHA512\n\n{RELEASE}- dashed\n\
+             -----BEGIN PGP SIGNATURE-----\nxyz\n-----END PGP SIGNATURE-----\n"
+        );
+
+        assert_eq!(strip_signature(signed.as_str()), format!("{RELEASE}dashed"));
+        assert_eq!(strip_signature(RELEASE), RELEASE);
+    }
+
+    #[test]
+    fn reads_components_with_indices() {
+        let prefix = "lists/deb.debian.org_debian_dists_bookworm_";
+        let root = TestRoot::new()
+            .with(format!("{prefix}InRelease").as_str(), RELEASE)
+            .with(format!("{prefix}Release").as_str(), "Suite: ignored\n")
+            .with(format!("{prefix}main_binary-amd64_Packages").as_str(), "")
+            .with(
+                format!("{prefix}non-free-firmware_binary-amd64_Packages").as_str(),
+                "",
+            )
+            .with(
+                "lists/example.org_repo_._Release",
+                "Origin: example\nNotAutomatic: yes\nButAutomaticUpgrades: yes\n",
+            )
+            .with("lists/example.org_repo_._Packages", "");
+
+        let files = read_release_files(root.path().join("lists").as_path()).unwrap();
+
+        assert_eq!(files.len(), 2);
+        let debian = &files[0];
+        assert_eq!(debian.host, "deb.debian.org");
+        assert_eq!(debian.policy.suite.as_deref(), Some("stable"));
+        assert_eq!(debian.policy.version.as_deref(), Some("12.5"));
+        assert_eq!(
+            debian.components,
+            [
+                Some("main".to_string()),
+                Some("non-free-firmware".to_string())
+            ]
+        );
+        assert_eq!(debian.default_priority, 500);
+
+        let flat = &files[1];
+        assert_eq!(flat.host, "example.org");
+        assert_eq!(flat.components, [None]);
+        assert_eq!(flat.default_priority, 100);
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+/// One paragraph of a Debian control file, such as an APT `Release` file, an APT preferences
+/// file or the dpkg status database.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub(in crate::lsb_release::imp) struct Paragraph {
+    // keys are lowercase, because field names are case-insensitive
+    fields: HashMap<String, String>,
+}
+
+impl Paragraph {
+    /// Returns the value of the field `name`, looked up case-insensitively.
+    ///
+    /// For multi-line fields, continuation lines are joined with `\n`.
+    pub(in crate::lsb_release::imp) fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .get(name.to_lowercase().as_str())
+            .map(String::as_str)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Splits `text` into paragraphs, which are separated by blank lines.
+///
+/// Lines starting with `#` are comments. Lines which are neither a `Field: value` pair nor a
+/// continuation of the previous field are ignored.
+pub(in crate::lsb_release::imp) fn parse_paragraphs(text: &str) -> Vec<Paragraph> {
+    let mut paragraphs = vec![];
+    let mut current = Paragraph::default();
+    let mut last_field: Option<String> = None;
+
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            last_field = None;
+            continue;
+        }
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last_field
+                .as_ref()
+                .and_then(|field| current.fields.get_mut(field))
+            {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            continue;
+        }
+
+        if let Some((field, value)) = line.split_once(':') {
+            let field = field.trim().to_lowercase();
+            current
+                .fields
+                .insert(field.clone(), value.trim().to_string());
+            last_field = Some(field);
+        }
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_paragraphs_and_joins_continuations() {
+        let paragraphs = parse_paragraphs(
+            "# a comment\nPackage: lsb-core\nDescription: short\n long\n\tlonger\n\n\n\
+             package: other\nbroken line\nStatus:   install ok installed  \n",
+        );
+
+        assert_eq!(paragraphs.len(), 2);
+        assert_eq!(paragraphs[0].get("Package"), Some("lsb-core"));
+        assert_eq!(
+            paragraphs[0].get("description"),
+            Some("short\nlong\nlonger")
+        );
+        assert_eq!(paragraphs[1].get("PACKAGE"), Some("other"));
+        assert_eq!(paragraphs[1].get("Status"), Some("install ok installed"));
+        assert_eq!(paragraphs[1].get("broken line"), None);
+    }
+
+    #[test]
+    fn ignores_blank_input() {
+        assert!(parse_paragraphs("").is_empty());
+        assert!(parse_paragraphs("\n  \n# only a comment\n").is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+use crate::lsb_release::error::LsbError;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A Debian package version, such as `1:4.1+Debian13+nmu1-2`, ordered the way dpkg orders them.
+///
+/// The version is split into an epoch (before the first `:`), an upstream version and a Debian
+/// revision (after the last `-`). Epochs compare numerically; the other two parts compare
+/// alternately by their non-digit and digit runs, where `~` sorts before anything, even the end
+/// of the string, so that `1.0~rc1 < 1.0 < 1.0+deb12u1`.
+///
+/// Equality follows the ordering: `1.0` equals `1.0-0` and `01.0`.
+///
+/// Parsing rejects what dpkg rejects or warns about, except an upstream version which does not
+/// start with a digit, such as the `bookworm` of a suite: the upstream version may only contain
+/// ASCII letters, digits and `.+~-:`, and the revision only ASCII letters, digits and `.+~`.
+#[derive(Clone, Debug)]
+pub struct DebVersion {
+    epoch: u64,
+    upstream: String,
+    revision: String,
+}
+
+impl DebVersion {
+    /// The epoch, or `0` if there is none.
+    #[must_use]
+    pub const fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The upstream version, without the epoch and the Debian revision.
+    #[must_use]
+    pub const fn upstream(&self) -> &str {
+        self.upstream.as_str()
+    }
+
+    /// The Debian revision, or an empty string for a native package.
+    #[must_use]
+    pub const fn revision(&self) -> &str {
+        self.revision.as_str()
+    }
+}
+
+impl FromStr for DebVersion {
+    type Err = LsbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| LsbError::Parse {
+            context: format!("version `{s}`"),
+            reason: reason.to_string(),
+        };
+
+        let version = s.trim();
+        if version.chars().any(char::is_whitespace) {
+            return Err(invalid("it contains whitespace"));
+        }
+
+        let (epoch, rest) = match version.split_once(':') {
+            Some((epoch, rest)) => {
+                let epoch = epoch
+                    .parse()
+                    .map_err(|_| invalid("the epoch is not a number"))?;
+                (epoch, rest)
+            }
+            None => (0, version),
+        };
+        let (upstream, revision) = match rest.rsplit_once('-') {
+            Some((_, "")) => return Err(invalid("the revision is empty")),
+            Some(split) => split,
+            None => (rest, ""),
+        };
+
+        if upstream.is_empty() {
+            return Err(invalid("the upstream version is empty"));
+        }
+        let is_valid =
+            |c: char, punctuation: &str| c.is_ascii_alphanumeric() || punctuation.contains(c);
+        if !upstream.chars().all(|c| is_valid(c, ".+~-:")) {
+            return Err(invalid(
+                "the upstream version contains an invalid character",
+            ));
+        }
+        if !revision.chars().all(|c| is_valid(c, ".+~")) {
+            return Err(invalid("the revision contains an invalid character"));
+        }
+
+        Ok(Self {
+            epoch,
+            upstream: upstream.to_string(),
+            revision: revision.to_string(),
+        })
+    }
+}
+
+impl Display for DebVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}:", self.epoch)?;
+        }
+        write!(f, "{}", self.upstream)?;
+        if !self.revision.is_empty() {
+            write!(f, "-{}", self.revision)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Ord for DebVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| verrevcmp(self.upstream.as_bytes(), other.upstream.as_bytes()))
+            .then_with(|| verrevcmp(self.revision.as_bytes(), other.revision.as_bytes()))
+    }
+}
+
+impl PartialOrd for DebVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for DebVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DebVersion {}
+
+// the weight of a character in a non-digit run, as in dpkg's `order()`
+fn order(c: Option<&u8>) -> i32 {
+    match c {
+        Some(b'~') => -1,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => i32::from(*c),
+        Some(c) => i32::from(*c) + 256,
+        None => 0,
+    }
+}
+
+// this is dpkg's `verrevcmp()`
+fn verrevcmp(mut a: &[u8], mut b: &[u8]) -> Ordering {
+    let is_digit = |s: &[u8]| s.first().is_some_and(u8::is_ascii_digit);
+
+    while !a.is_empty() || !b.is_empty() {
+        while (!a.is_empty() && !is_digit(a)) || (!b.is_empty() && !is_digit(b)) {
+            let (ac, bc) = (order(a.first()), order(b.first()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            a = a.get(1..).unwrap_or_default();
+            b = b.get(1..).unwrap_or_default();
+        }
+
+        while a.first() == Some(&b'0') {
+            a = &a[1..];
+        }
+        while b.first() == Some(&b'0') {
+            b = &b[1..];
+        }
+
+        let mut first_diff = Ordering::Equal;
+        while is_digit(a) && is_digit(b) {
+            if first_diff == Ordering::Equal {
+                first_diff = a[0].cmp(&b[0]);
+            }
+            a = &a[1..];
+            b = &b[1..];
+        }
+
+        if is_digit(a) {
+            return Ordering::Greater;
+        }
+        if is_digit(b) {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pairs of versions where the first sorts before the second, as `dpkg --compare-versions`
+    /// says.
+    const ASCENDING: &[(&str, &str)] = &[
+        ("1.0~", "1.0"),
+        ("1.0~~", "1.0~"),
+        ("1.0~rc1", "1.0"),
+        ("1.0~rc1", "1.0~rc2"),
+        ("1.0", "1.0+deb12u1"),
+        ("1.0", "1.0.1"),
+        ("1.0a", "1.0+"),
+        ("1.0Z", "1.0a"),
+        ("1.0", "1.0a"),
+        ("1.9", "1.10"),
+        ("9.9", "1:0.1"),
+        ("1:9", "2:0"),
+        ("1.0-1", "1.0-1.1"),
+        ("1.0-1", "1.0-2"),
+        ("1.0-9", "1.0-10"),
+        ("1.0-1", "1.0.1-1"),
+        ("4.1+Debian13", "4.1+Debian13+nmu1"),
+    ];
+
+    fn parse(version: &str) -> DebVersion {
+        version.parse().unwrap()
+    }
+
+    #[test]
+    fn orders_like_dpkg() {
+        for (lower, higher) in ASCENDING {
+            assert!(parse(lower) < parse(higher), "{lower} < {higher}");
+            assert!(parse(higher) > parse(lower), "{higher} > {lower}");
+        }
+    }
+
+    #[test]
+    fn treats_a_missing_revision_and_epoch_as_zero() {
+        assert_eq!(parse("1.0"), parse("1.0-0"));
+        assert_eq!(parse("1.0"), parse("0:1.0"));
+        assert_eq!(parse("1.0"), parse("01.00"));
+        assert_ne!(parse("1.0"), parse("1.0-1"));
+    }
+
+    #[test]
+    fn orders_consistently() {
+        let versions = ASCENDING
+            .iter()
+            .flat_map(|(lower, higher)| [parse(lower), parse(higher)])
+            .collect::<Vec<_>>();
+
+        for a in &versions {
+            assert_eq!(a.cmp(a), Ordering::Equal);
+            for b in &versions {
+                assert_eq!(a.cmp(b), b.cmp(a).reverse(), "{a} and {b}");
+                for c in &versions {
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{a} <= {b} <= {c}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn splits_epoch_upstream_and_revision() {
+        let version = parse("1:4.1-0+Debian13-2");
+
+        assert_eq!(version.epoch(), 1);
+        assert_eq!(version.upstream(), "4.1-0+Debian13");
+        assert_eq!(version.revision(), "2");
+        assert_eq!(version.to_string(), "1:4.1-0+Debian13-2");
+        assert_eq!(parse("bookworm").upstream(), "bookworm");
+    }
+
+    #[test]
+    fn rejects_what_dpkg_rejects() {
+        for version in [
+            "",
+            "1.0 1",
+            ":1.0",
+            "a:1.0",
+            "1:",
+            "1.0-",
+            "-1",
+            "1.0_1",
+            "1.0-1:2",
+            "1.0-1-a/b",
+            "1.0@",
+        ] {
+            assert!(version.parse::<DebVersion>().is_err(), "{version}");
+        }
+    }
+}
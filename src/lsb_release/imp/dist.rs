@@ -1,28 +1,49 @@
+use crate::lsb_release::error::LsbError;
 use crate::lsb_release::imp::apt::{dpkg_default_vendor, parse_apt_policy, AptPolicy};
-use crate::lsb_release::imp::lsb::valid_lsb_versions;
+use crate::lsb_release::imp::deb_version::DebVersion;
+use crate::lsb_release::imp::distro_info::{DistroInfoTable, DistroRelease};
+use crate::lsb_release::imp::dpkg::DpkgStatus;
+use crate::lsb_release::imp::etc_lsb_release::EtcLsbRelease;
+use crate::lsb_release::imp::lsb::{LsbCompatTable, LsbPackage};
+use crate::lsb_release::imp::lsb_module::LsbModule;
+use crate::lsb_release::imp::os_release::OsRelease;
+use crate::lsb_release::imp::paths::PathGetter;
+use crate::lsb_release::imp::rpm;
+use chrono::Local;
 use fancy_regex::Regex;
 use once_cell::sync::Lazy;
-use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
-use std::env::var;
-use std::error::Error;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use voca_rs::Voca;
 
 static MOD_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"lsb-(?P<module>[a-z\d]+)-(?P<arch>[^ ]+)(?: \(= (?P<version>[\d.]+)\))?"#)
-        .unwrap()
+    // module names may contain `-`, as in `lsb-toolkit-gtk-amd64`, and so may the architectures of
+    // the non-Linux ports, as in `lsb-core-kfreebsd-amd64`; other architectures never do
+    Regex::new(
+        r#"^\s*lsb-(?P<module>[a-z\d-]+?)-(?P<arch>(?:hurd-|kfreebsd-|knetbsd-|kopensolaris-)?[^ -]+)(?: \(= (?P<version>[\d.]+)\))?\s*$"#,
+    )
+    .unwrap()
 });
 
-#[derive(Eq, PartialEq, Default, Clone)]
-pub(in crate::lsb_release) struct DistroInfo {
-    pub(in crate::lsb_release) release: Option<String>,
-    pub(in crate::lsb_release) codename: Option<String>,
-    pub(in crate::lsb_release) id: Option<String>,
-    pub(in crate::lsb_release) description: Option<String>,
+static DEBIAN_VERSION_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(\d+)\.(\d+)(r(\d+))?"#).unwrap());
+
+/// What `lsb_release` reports about the running distribution.
+///
+/// Every field is `None` if it could not be detected.
+#[derive(Eq, PartialEq, Default, Clone, Debug)]
+#[non_exhaustive]
+pub struct DistroInfo {
+    /// Version number of the release, such as `12`. Printed by `-r`.
+    pub release: Option<String>,
+    /// Codename of the release, such as `bookworm`. Printed by `-c`.
+    pub codename: Option<String>,
+    /// Distributor ID, such as `Debian`. Printed by `-i`.
+    pub id: Option<String>,
+    /// Human-readable description, such as `Debian GNU/Linux 12 (bookworm)`. Printed by `-d`.
+    pub description: Option<String>,
 }
 
 #[derive(Default, Eq, PartialEq, Debug)]
@@ -32,13 +53,20 @@ struct DebianRelease {
 }
 
 impl DistroInfo {
-    const fn is_partial(&self) -> bool {
+    const fn is_empty(&self) -> bool {
         self.release.is_none()
             && self.codename.is_none()
             && self.id.is_none()
             && self.description.is_none()
     }
 
+    const fn is_complete(&self) -> bool {
+        self.release.is_some()
+            && self.codename.is_some()
+            && self.id.is_some()
+            && self.description.is_some()
+    }
+
     fn merged(&self, other: &Self) -> Self {
         Self {
             release: self.release.as_ref().or(other.release.as_ref()).cloned(),
@@ -52,18 +80,47 @@ impl DistroInfo {
         }
     }
 
+    /// Loads the distro-info table of `id`.
+    pub(in crate::lsb_release) fn distro_info_table(
+        &self,
+        paths: &PathGetter,
+    ) -> Option<DistroInfoTable> {
+        DistroInfoTable::load_with(paths, self.id.as_ref()?).ok()
+    }
+
+    /// Finds the row of `table` which describes this release, by its codename or else by its
+    /// version number; `12.4` matches the row of Debian 12.
+    pub(in crate::lsb_release) fn find_in<'t>(
+        &self,
+        table: &'t DistroInfoTable,
+    ) -> Option<&'t DistroRelease> {
+        let by_codename = || table.by_series(self.codename.as_ref()?);
+        let by_version = || table.by_version(self.release.as_ref()?);
+
+        by_codename().or_else(by_version)
+    }
+
     // this is guess_debian_release()
-    fn guess_debian_release() -> Result<Self, Box<dyn Error>> {
+    fn guess_debian_release(paths: &PathGetter) -> Result<Self, LsbError> {
         let mut lsbinfo = Self {
             id: Some("Debian".to_string()),
             ..DistroInfo::default()
         };
-        lsbinfo.id = dpkg_default_vendor().ok().flatten();
+        if let Some(vendor) = dpkg_default_vendor(paths).ok().flatten() {
+            lsbinfo.id = Some(vendor);
+        }
 
-        let x = DistroReleases::get_distro_info(lsbinfo.id.clone());
+        // without a distro-info table, the release is still known but its codename is not
+        let x = DistroReleases::get_distro_info(lsbinfo.id.clone(), paths).unwrap_or_else(|_| {
+            DistroReleases::new(lsbinfo.id.clone(), DistroInfoTable::default())
+        });
 
+        let uname = uname_rs::Uname::new().map_err(|source| LsbError::Io {
+            context: "uname".to_string(),
+            source,
+        })?;
         #[allow(unused_variables)]
-        let os = match uname_rs::Uname::new()?.sysname.as_str() {
+        let os = match uname.sysname.as_str() {
             #[allow(unused_variables)]
             x @ ("Linux" | "Hurd" | "NetBSD") => format!("GNU/{x}"),
             "FreeBSD" => "GNU/kFreeBSD".to_string(),
@@ -72,27 +129,28 @@ impl DistroInfo {
         };
 
         lsbinfo.description = Some(format!(
-            "{id}s {os}s",
+            "{id} {os}",
             id = lsbinfo.id.clone().unwrap_or_default()
         ));
 
-        let y = x.get_debian_release()?;
+        let y = x.get_debian_release(paths)?;
         lsbinfo.release = y.release;
         lsbinfo.codename = y.codename;
 
         if lsbinfo.codename.is_none() {
-            let rinfo = x.guess_release_from_apt(None, None, None, None, None);
+            let rinfo = x.guess_release_from_apt(paths, None, None, None, None, None);
             if let Some(mut rinfo) = rinfo {
                 let release = rinfo.version.and_then(|release| {
-                    let condition = rinfo.origin.unwrap() == *"Debian Ports"
-                        && ["ftp.ports.debian.org", "ftp.debian-ports.org"]
-                            .contains(&rinfo.label.unwrap().as_str());
+                    let condition = rinfo.origin.as_deref() == Some("Debian Ports")
+                        && rinfo.label.as_deref().is_some_and(|label| {
+                            ["ftp.ports.debian.org", "ftp.debian-ports.org"].contains(&label)
+                        });
 
                     if condition {
                         rinfo.suite = Some("unstable".to_string());
                     }
 
-                    (!condition).then(|| release)
+                    (!condition).then_some(release)
                 });
 
                 let codename = release.clone().map_or_else(
@@ -117,80 +175,160 @@ impl DistroInfo {
         }
 
         if let Some(ref codename) = lsbinfo.codename {
-            lsbinfo.description = lsbinfo.description.map(|d| format!("{d} {codename}"));
+            lsbinfo.description = lsbinfo.description.map(|d| format!("{d} ({codename})"));
         }
 
         Ok(lsbinfo)
     }
 
     // this is get_os_release()
-    fn get_partial_info(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
-        let read = File::open(path)?;
-        let read = BufReader::new(read);
-        let mut info = Self::default();
-        for line4 in read.lines() {
-            // borrow checker :c
-            let q = line4.unwrap();
-            let line = q.as_str().trim();
-            if line.is_empty() {
-                continue;
-            }
+    fn get_partial_info(path: &Path) -> Result<Self, LsbError> {
+        let os_release = OsRelease::read(path).map_err(LsbError::io(path))?;
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
 
-            if !line.contains('=') {
-                continue;
-            }
+        Ok(Self {
+            release: non_empty(os_release.version_id),
+            codename: non_empty(os_release.version_codename),
+            id: non_empty(os_release.id).map(|id| id._title_case()),
+            description: non_empty(os_release.pretty_name),
+        })
+    }
 
-            let elements = line.splitn(2, '=').collect::<Vec<_>>();
-            let (var, arg) = (elements[0], elements[1]);
-            // SAFETY: (forall 'a) ^ (a: &'a str) ^ (b: &'a str) ^ a.starts_with(b)
-            //   supports a.strip_prefix(b).unwrap() safety.
-            //   ends_with and strip_suffix as well.
-            let arg = {
-                if arg.starts_with('"') && arg.ends_with('"') {
-                    arg.strip_suffix('"').unwrap().strip_prefix('"').unwrap()
-                } else {
-                    arg
-                }
-            };
+    /// Detects the running distribution.
+    ///
+    /// `/etc/lsb-release` is consulted first and `os-release` fills in what it lacks, as the
+    /// Python `lsb_release` does. If neither provides any of the fields, they are guessed from
+    /// `/etc/debian_version`, dpkg origins and APT instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither `/etc/lsb-release` nor `os-release` can be read, or if the
+    /// fallback guess fails.
+    pub fn get_distro_information() -> Result<Self, LsbError> {
+        Self::get_distro_information_in("/")
+    }
 
-            if arg.is_empty() {
-                continue;
-            }
+    /// Same as [`DistroInfo::get_distro_information`], but for the system installed in `root`,
+    /// such as a chroot or a mounted image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither `/etc/lsb-release` nor `os-release` can be read, or if the
+    /// fallback guess fails.
+    pub fn get_distro_information_in(root: impl Into<PathBuf>) -> Result<Self, LsbError> {
+        Self::detect(&PathGetter::new(root))
+    }
 
-            match var {
-                "VERSION_ID" => {
-                    info.release = Some(arg.trim().to_string());
-                }
-                "VERSION_CODENAME" => {
-                    info.codename = Some(arg.trim().to_string());
-                }
-                "ID" => {
-                    info.id = Some(arg.trim()._title_case());
-                }
-                "PRETTY_NAME" => {
-                    info.description = Some(arg.trim().to_string());
-                }
+    /// Describes the distribution the running one is derived from, like `lsb_release -u` of
+    /// Linux Mint and elementary OS does.
+    ///
+    /// `/etc/upstream-release/lsb-release` is read first. Whatever it lacks is resolved from
+    /// `ID_LIKE` in `os-release`: the first distribution named there whose distro-info table has
+    /// the release named by its `<ID>_CODENAME` variable, such as `UBUNTU_CODENAME`, or else by
+    /// `VERSION_CODENAME` is taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither source describes an upstream distribution.
+    pub fn get_upstream_distro_information() -> Result<Self, LsbError> {
+        Self::get_upstream_distro_information_in("/")
+    }
 
-                _ => {}
-            }
+    /// Same as [`DistroInfo::get_upstream_distro_information`], but for the system installed in
+    /// `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither source describes an upstream distribution.
+    pub fn get_upstream_distro_information_in(root: impl Into<PathBuf>) -> Result<Self, LsbError> {
+        Self::detect_upstream(&PathGetter::new(root))
+    }
+
+    pub(in crate::lsb_release) fn detect_upstream(paths: &PathGetter) -> Result<Self, LsbError> {
+        let upstream = EtcLsbRelease::read(paths.etc_upstream_lsb_release())
+            .map(|lsb_release| lsb_release.info)
+            .unwrap_or_default();
+
+        if upstream.is_complete() {
+            return Ok(upstream);
+        }
+
+        match Self::guess_from_id_like(paths) {
+            Ok(id_like) => Ok(upstream.merged(&id_like)),
+            Err(_) if !upstream.is_empty() => Ok(upstream),
+            Err(e) => Err(e),
         }
-        Ok(info)
     }
 
-    pub(in crate::lsb_release) fn get_distro_information() -> Result<Self, Box<dyn Error>> {
-        let lsbinfo = Self::get_partial_info(PathGetter::lsb_os_release())?;
-        if lsbinfo.is_partial() {
-            let lsbinfo = lsbinfo.merged(&Self::guess_debian_release()?);
+    fn guess_from_id_like(paths: &PathGetter) -> Result<Self, LsbError> {
+        let path = paths.lsb_os_release();
+        let os_release = OsRelease::read(&path).map_err(LsbError::io(&path))?;
+
+        os_release
+            .id_like
+            .iter()
+            .find_map(|like| {
+                let table = DistroInfoTable::load_with(paths, like).ok()?;
+                let id = like._title_case();
+                let record = os_release
+                    .extra
+                    .get(format!("{}_CODENAME", like.to_uppercase()).as_str())
+                    .or(os_release.version_codename.as_ref())
+                    .and_then(|codename| table.by_series(codename))?;
+
+                Some(Self {
+                    release: record.version_number().map(ToString::to_string),
+                    codename: Some(record.series.clone()),
+                    description: record
+                        .version
+                        .as_ref()
+                        .map(|version| format!("{id} {version}")),
+                    id: Some(id),
+                })
+            })
+            .ok_or(LsbError::NotDetected("the upstream distribution"))
+    }
+
+    pub(in crate::lsb_release) fn detect(paths: &PathGetter) -> Result<Self, LsbError> {
+        let lsb_release = EtcLsbRelease::read(paths.etc_lsb_release())
+            .map(|lsb_release| lsb_release.info)
+            .unwrap_or_default();
+
+        // without os-release, whatever /etc/lsb-release says is kept and the rest is guessed
+        let lsbinfo = Self::get_partial_info(&paths.lsb_os_release()).map_or_else(
+            |_| lsb_release.clone(),
+            |os_release| lsb_release.merged(&os_release),
+        );
+
+        // as in the Python implementation, whatever is still missing is guessed
+        if lsbinfo.is_complete() {
             return Ok(lsbinfo);
         }
 
-        Ok(lsbinfo)
+        match Self::guess_debian_release(paths) {
+            Ok(guess) => Ok(lsbinfo.merged(&guess)),
+            Err(_) if !lsbinfo.is_empty() => Ok(lsbinfo),
+            Err(e) => Err(e),
+        }
     }
 }
 
+/// How new a suite is; releases among the same APT priority are tried in this order.
+#[derive(Eq, PartialEq, Ord, PartialOrd)]
+enum SuiteRank {
+    Unknown,
+    /// Distance from the end of the release order, so that newer suites come first.
+    Named(usize),
+    Version(DebVersion),
+}
+
 #[derive(Eq, PartialEq)]
 struct DistroReleases {
-    codename_lookup: Vec<DistroInfoCsvRecord>,
+    codename_lookup: DistroInfoTable,
     release_order: Vec<String>,
     debian_testing_codename: Option<String>,
 }
@@ -198,13 +336,14 @@ struct DistroReleases {
 impl DistroReleases {
     fn guess_release_from_apt(
         &self,
+        paths: &PathGetter,
         origin: Option<String>,
         component: Option<String>,
         ignore_suites: Option<Vec<String>>,
         label: Option<String>,
         alternate_ports: Option<HashMap<String, Vec<String>>>,
     ) -> Option<AptPolicy> {
-        let releases = parse_apt_policy();
+        let releases = parse_apt_policy(paths);
         let origin = origin.unwrap_or_else(|| "Debian".to_string());
         let component = component.unwrap_or_else(|| "main".to_string());
         let ignore_suites = ignore_suites.unwrap_or_else(|| vec!["experimental".to_string()]);
@@ -259,19 +398,15 @@ impl DistroReleases {
             .iter()
             .filter(|x| x.priority == max_priority)
             .collect::<Vec<_>>();
-        releases.sort_by_key(|a| {
-            let policy = a.policy.suite.as_ref();
-
-            policy.map_or(0, |suite| {
-                if self.release_order.contains(suite) {
-                    // NOTE: do you think you can contain 2^63 elements in your memory?
-                    (self.release_order.len()
-                        - self.release_order.iter().position(|a| a == suite).unwrap())
-                        as isize
-                } else {
-                    // FIXME: this is not correct in strict manner.
-                    suite.parse::<f64>().unwrap_or(0.0) as isize
-                }
+        releases.sort_by_cached_key(|a| {
+            a.policy.suite.as_ref().map_or(SuiteRank::Unknown, |suite| {
+                self.release_order
+                    .iter()
+                    .position(|a| a == suite)
+                    .map_or_else(
+                        || suite.parse().map_or(SuiteRank::Unknown, SuiteRank::Version),
+                        |position| SuiteRank::Named(self.release_order.len() - position),
+                    )
             })
         });
 
@@ -279,40 +414,32 @@ impl DistroReleases {
     }
 
     fn lookup_codename(&self, release: &str) -> Option<String> {
-        let regex = Regex::new(r#"(\d+)\.(\d+)(r(\d+))?"#).unwrap();
-        regex.captures(release).unwrap().and_then(|captures| {
-            let c1 = captures[1].parse::<u32>().unwrap();
-            let short = if c1 < 7 {
-                format!("{c1}.{c2}", c2 = &captures[2])
-            } else {
-                format!("{c1}")
-            };
+        let captures = DEBIAN_VERSION_REGEX.captures(release).ok().flatten()?;
+        let c1 = captures[1].parse::<u32>().ok()?;
+        let short = if c1 < 7 {
+            format!("{c1}.{c2}", c2 = &captures[2])
+        } else {
+            format!("{c1}")
+        };
 
-            self.codename_lookup
-                .iter()
-                .find(|p| p.version == short)
-                .map(|a| a.version.clone())
-        })
+        self.codename_lookup
+            .by_version(short.as_str())
+            .map(|a| a.series.clone())
     }
 
-    fn get_distro_info(origin: Option<String>) -> Self {
-        let origin = origin.unwrap_or_else(|| "Debian".to_string());
-        let csv_file = PathGetter::distro_info_csv(origin.as_str());
+    fn get_distro_info(origin: Option<String>, paths: &PathGetter) -> Result<Self, LsbError> {
+        // origins without a table of their own fall back to Debian's
+        let codename_lookup =
+            DistroInfoTable::load_with(paths, origin.as_deref().unwrap_or("Debian"))
+                .or_else(|_| DistroInfoTable::load_with(paths, "debian"))?;
 
-        let mut codename_lookup = csv::Reader::from_path(csv_file)
-            .unwrap()
-            .deserialize::<DistroInfoCsvRecord>()
-            .map(Result::unwrap)
-            .collect::<Vec<_>>();
-        // f64 is not Ord
-        codename_lookup.sort_by(|a, b| {
-            a.series
-                .parse::<f64>()
-                .unwrap()
-                .partial_cmp(&b.series.parse::<f64>().unwrap())
-                .unwrap()
-        });
+        Ok(Self::new(origin, codename_lookup))
+    }
+
+    fn new(origin: Option<String>, codename_lookup: DistroInfoTable) -> Self {
+        let origin = origin.unwrap_or_else(|| "Debian".to_string());
         let mut release_order = codename_lookup
+            .in_release_order()
             .iter()
             .map(|a| a.series.clone())
             .collect::<Vec<_>>();
@@ -327,38 +454,41 @@ impl DistroReleases {
                 "sid".to_string(),
             ]);
 
-            "unknown.new.testing"
+            codename_lookup
+                .testing(Local::now().date_naive())
+                .map(|testing| testing.series.clone())
         });
 
         Self {
-            codename_lookup,
             release_order,
-            debian_testing_codename: debian_testing_codename.map(std::string::ToString::to_string),
+            debian_testing_codename: debian_testing_codename.flatten(),
+            codename_lookup,
         }
     }
 
-    fn get_debian_release(&self) -> Result<DebianRelease, Box<dyn Error>> {
-        let path = PathGetter::debian_version();
-        let read_lines = &BufReader::new(File::open(path)?)
+    fn get_debian_release(&self, paths: &PathGetter) -> Result<DebianRelease, LsbError> {
+        let path = paths.debian_version();
+        let first_line = BufReader::new(File::open(&path).map_err(LsbError::io(&path))?)
             .lines()
-            .collect::<Vec<_>>();
-
-        let release = read_lines[0].as_ref();
+            .next()
+            .transpose()
+            .map_err(LsbError::io(&path))?;
 
-        // borrow checkers :c
-        let unknown = &"unknown".to_string();
-        let release = release.unwrap_or(unknown);
+        let release = first_line
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .unwrap_or_else(|| "unknown".to_string());
+        let release = release.as_str();
 
         let mut y = DebianRelease::default();
 
-        if !&release[0..=1]._is_alpha() {
+        if !release.chars().next().is_some_and(char::is_alphabetic) {
             let codename = self
                 .lookup_codename(release)
                 .unwrap_or_else(|| "n/a".to_string());
             y.codename = Some(codename);
             y.release = Some(release.to_string());
-        } else if release.ends_with("/sid") {
-            let strip = release.strip_suffix("/sid").unwrap();
+        } else if let Some(strip) = release.strip_suffix("/sid") {
             y.release = (strip.to_lowercase() != "testing").then(|| strip.to_string());
         } else {
             y.release = Some(release.to_string());
@@ -368,111 +498,160 @@ impl DistroReleases {
     }
 }
 
-struct PathGetter;
+/// Lists the LSB modules the installed `lsb-*` packages provide, such as `core-4.1-amd64`.
+///
+/// The dpkg database is looked up in `$DPKG_ADMINDIR`, or in `/var/lib/dpkg` if that is not set,
+/// like dpkg does. If it has no LSB packages, `rpm` is asked for packages which provide them, such
+/// as `redhat-lsb-core`, and failing that `LSB_VERSION` from `/etc/lsb-release` is used. Returns
+/// `None` if no module is available.
+///
+/// Each package also provides the modules of older LSB versions it is compatible with, as the
+/// [`LsbCompatTable`] of the system says.
+///
+/// # Errors
+///
+/// Returns an error if one of these sources exists but cannot be read. A missing dpkg database,
+/// `rpm` or `/etc/lsb-release` only means that it has no modules.
+pub fn lsb_version() -> Result<Option<Vec<LsbModule>>, LsbError> {
+    lsb_version_in_root("/")
+}
+
+/// Same as [`lsb_version`], but for the system installed in `root`, such as a chroot or a mounted
+/// image.
+///
+/// The environment variables of the running system are ignored unless `root` is `/`.
+///
+/// # Errors
+///
+/// Same as [`lsb_version`].
+pub fn lsb_version_in_root(root: impl Into<PathBuf>) -> Result<Option<Vec<LsbModule>>, LsbError> {
+    lsb_version_with_paths(&PathGetter::new(root))
+}
+
+pub(in crate::lsb_release) fn lsb_version_with_paths(
+    paths: &PathGetter,
+) -> Result<Option<Vec<LsbModule>>, LsbError> {
+    lsb_version_with_compat(paths, &LsbCompatTable::load_with(paths))
+}
 
-impl PathGetter {
-    fn lsb_os_release() -> impl AsRef<Path> {
-        var("LSB_OS_RELEASE").unwrap_or_else(|_| "/usr/lib/os-release".to_string())
+/// Same as `lsb_version_with_paths`, with the module table already loaded.
+pub(in crate::lsb_release) fn lsb_version_with_compat(
+    paths: &PathGetter,
+    compat: &LsbCompatTable,
+) -> Result<Option<Vec<LsbModule>>, LsbError> {
+    if let Some(modules) = lsb_modules_in_dpkg(paths.dpkg_admindir().as_path(), compat)? {
+        return Ok(Some(modules));
     }
 
-    fn distro_info_csv(#[allow(unused_variables)] origin: &str) -> impl AsRef<Path> {
-        let path = format!("/usr/share/distro-info/{origin}.csv");
-        if Path::new(&path).exists() {
-            path
-        } else {
-            // fallback
-            "/usr/share/distro-info/debian.csv".to_string()
+    // rpm is not even run where it has no database, such as on Debian with rpm installed
+    if paths.rpm_database().is_some() {
+        match rpm::installed_lsb_packages(paths) {
+            Ok(installed) => {
+                if let Some(modules) = lsb_modules(&installed, compat) {
+                    return Ok(Some(modules));
+                }
+            }
+            Err(LsbError::MissingTool(_)) => {}
+            Err(e) => return Err(e),
         }
     }
 
-    fn debian_version() -> impl AsRef<Path> {
-        var("LSB_ETC_DEBIAN_VERSION").unwrap_or_else(|_| "/etc/debian_version".to_string())
+    let path = paths.etc_lsb_release();
+    match EtcLsbRelease::read(&path) {
+        Ok(lsb_release) => Ok(lsb_release
+            .lsb_version
+            .filter(|modules| !modules.is_empty())),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(LsbError::io(&path)(e)),
     }
 }
 
-pub(in crate::lsb_release) fn lsb_version() -> Option<Vec<String>> {
-    let mut dpkg_query_args = vec![
-        "-f".to_string(),
-        // NOTE: this is dpkg-query formatter, no need to interpolate
-        format!("${{Version}} ${{Provides}}\n"),
-        "-W".to_string(),
-    ];
+/// Same as [`lsb_version`], but reads the dpkg database in `admindir`, like `dpkg --admindir`.
+///
+/// This works on a chroot or image whose dpkg cannot be run. If `admindir` is the `var/lib/dpkg`
+/// of such a system, the [`LsbCompatTable`] is taken from that system too; otherwise only the
+/// built-in table is used.
+///
+/// # Errors
+///
+/// Returns an error if the database exists but cannot be read.
+pub fn lsb_version_in(admindir: impl AsRef<Path>) -> Result<Option<Vec<LsbModule>>, LsbError> {
+    let admindir = admindir.as_ref();
+    let root = admindir
+        .ends_with("var/lib/dpkg")
+        .then(|| admindir.ancestors().nth(3))
+        .flatten();
+    let compat = root.map_or_else(LsbCompatTable::embedded, |root| {
+        LsbCompatTable::load_with(&PathGetter::new(root))
+    });
+
+    lsb_modules_in_dpkg(admindir, &compat)
+}
+
+fn lsb_modules_in_dpkg(
+    admindir: &Path,
+    compat: &LsbCompatTable,
+) -> Result<Option<Vec<LsbModule>>, LsbError> {
+    let status = match DpkgStatus::load(admindir) {
+        Ok(status) => status,
+        // not a dpkg-based system
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(LsbError::io(admindir.join("status").as_path())(e)),
+    };
 
     // NOTE: this list may grow eventually!
-    let mut packages = vec![
-        "lsb-core".to_string(),
-        "lsb-cxx".to_string(),
-        "lsb-graphics".to_string(),
-        "lsb-desktop".to_string(),
-        "lsb-languages".to_string(),
-        "lsb-multimedia".to_string(),
-        "lsb-printing".to_string(),
-        "lsb-security".to_string(),
+    let packages = [
+        "lsb-core",
+        "lsb-cxx",
+        "lsb-graphics",
+        "lsb-desktop",
+        "lsb-imaging",
+        "lsb-languages",
+        "lsb-multimedia",
+        "lsb-printing",
+        "lsb-security",
+        "lsb-toolkit-gtk",
+        "lsb-trialuse",
     ];
 
-    dpkg_query_args.append(&mut packages);
-
-    #[allow(unused_variables)]
-    let dpkg_query_result = Command::new("dpkg-query")
-        .args(dpkg_query_args)
-        // void dpkg-query error, such as "no such package"
-        .stderr(Stdio::null())
-        // don't inherit
-        .stdout(Stdio::piped())
-        .spawn()
-        .ok()?
-        .wait_with_output()
-        .ok()?;
+    let installed = packages
+        .iter()
+        .flat_map(|package| status.installed(package))
+        .filter_map(|package| {
+            // the LSB version is the leading `major.minor` of the upstream version, such as `4.1`
+            // of `4.1+Debian13+nmu1`
+            let version = package.version.parse::<DebVersion>().ok()?;
+            let lsb_version = lsb_spec_version(version.upstream());
+
+            (!lsb_version.is_empty()).then(|| LsbPackage {
+                lsb_version: lsb_version.to_string(),
+                provides: package.provides,
+            })
+        })
+        .collect::<Vec<_>>();
 
-    let query_result_lines = dpkg_query_result.stdout;
-    if query_result_lines.is_empty() {
-        return None;
-    }
+    Ok(lsb_modules(&installed, compat))
+}
 
+/// The modules `installed` provide, whichever package manager they come from.
+fn lsb_modules(installed: &[LsbPackage], compat: &LsbCompatTable) -> Option<Vec<LsbModule>> {
     let mut modules = HashSet::new();
-    for line in String::from_utf8(query_result_lines)
-        .expect("It's not valid UTF-8")
-        .lines()
-    {
-        if line.is_empty() {
-            continue;
-        }
-
-        let elements = line.splitn(2, ' ').collect::<Vec<_>>();
-        let (version, provides) = (elements[0], elements[1]);
-        // NOTE: `as_str` for arbitrary `for<'a> SplitN<'a, P: Pattern>` is unstable:
-        //       it requires `str_split_as_str` as of 1.60.0
-        let version = {
-            // Debian revision splitter is one of them
-            ['-', '+', '~']
-                .into_iter()
-                .find(|a| version.contains(*a))
-                .map_or(version, |s| version.splitn(2, s).collect::<Vec<_>>()[0])
-        };
+    for package in installed {
+        let (version, provides) = (package.lsb_version.as_str(), package.provides.as_str());
 
         for pkg in provides.split(',') {
-            let named_groups = match MOD_NAME_REGEX.captures(pkg).unwrap() {
+            let named_groups = match MOD_NAME_REGEX.captures(pkg).ok().flatten() {
                 None => continue,
                 Some(captures) => captures,
             };
 
             let module = &named_groups["module"];
-            // false-positive on IDEA-side. see https://github.com/intellij-rust/intellij-rust/issues/8417
-            #[allow(unused_variables)]
             let arch = &named_groups["arch"];
-            if named_groups.name("version").is_some() {
-                #[allow(unused_variables)]
-                let version = &named_groups["version"];
-                let module = format!("{module}s-{version}s-{arch}s");
-
-                modules.insert(module);
+            if let Some(version) = named_groups.name("version") {
+                modules.insert(LsbModule::new(module, version.as_str(), arch));
             } else {
-                for v in valid_lsb_versions(version, module) {
-                    #[allow(unused_variables)]
-                    let version = v;
-                    let module = format!("{module}s-{version}s-{arch}s");
-
-                    modules.insert(module);
+                for version in compat.compatible_versions(version, module) {
+                    modules.insert(LsbModule::new(module, version, arch));
                 }
             }
         }
@@ -485,8 +664,248 @@ pub(in crate::lsb_release) fn lsb_version() -> Option<Vec<String>> {
     })
 }
 
-#[derive(Deserialize, Eq, PartialEq, Clone)]
-struct DistroInfoCsvRecord {
-    version: String,
-    series: String,
+fn lsb_spec_version(upstream: &str) -> &str {
+    let end = upstream
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(upstream.len());
+    upstream[..end].trim_end_matches('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    const OS_RELEASE: &str = "PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nID=debian\n\
+                              VERSION_ID=\"12\"\nVERSION_CODENAME=bookworm\n";
+
+    fn detect(root: &TestRoot) -> Result<DistroInfo, LsbError> {
+        DistroInfo::detect(&PathGetter::new(root.path()))
+    }
+
+    fn info(id: &str, description: &str, release: &str, codename: &str) -> DistroInfo {
+        DistroInfo {
+            release: Some(release.to_string()),
+            codename: Some(codename.to_string()),
+            id: Some(id.to_string()),
+            description: Some(description.to_string()),
+        }
+    }
+
+    #[test]
+    fn prefers_lsb_release_to_os_release() {
+        let root = TestRoot::new()
+            .with(
+                "etc/lsb-release",
+                "DISTRIB_ID=Custom\nDISTRIB_DESCRIPTION=\"Custom 1\"\n",
+            )
+            .with("etc/os-release", OS_RELEASE);
+
+        assert_eq!(
+            detect(&root).unwrap(),
+            info("Custom", "Custom 1", "12", "bookworm")
+        );
+    }
+
+    #[test]
+    fn guesses_only_what_is_missing() {
+        let root = TestRoot::new()
+            .with(
+                "etc/os-release",
+                "PRETTY_NAME=\"Debian GNU/Linux 12\"\nID=debian\nVERSION_ID=\"12\"\n",
+            )
+            .with("etc/debian_version", "12.5\n");
+
+        assert_eq!(
+            detect(&root).unwrap(),
+            info("Debian", "Debian GNU/Linux 12", "12", "bookworm")
+        );
+    }
+
+    #[test]
+    fn guesses_everything_without_os_release() {
+        let root = TestRoot::new()
+            .with("etc/debian_version", "12.5\n")
+            .with("etc/dpkg/origins/default", "Vendor: Debian\n");
+        let detected = detect(&root).unwrap();
+
+        assert_eq!(detected.id.as_deref(), Some("Debian"));
+        assert_eq!(detected.release.as_deref(), Some("12.5"));
+        assert_eq!(detected.codename.as_deref(), Some("bookworm"));
+        assert!(detected
+            .description
+            .is_some_and(|d| d.starts_with("Debian GNU") && d.ends_with(" 12.5 (bookworm)")));
+    }
+
+    #[test]
+    fn keeps_partial_information_when_the_guess_fails() {
+        let root = TestRoot::new().with("etc/lsb-release", "DISTRIB_ID=Custom\n");
+
+        assert_eq!(
+            detect(&root).unwrap(),
+            DistroInfo {
+                id: Some("Custom".to_string()),
+                ..DistroInfo::default()
+            }
+        );
+        assert!(detect(&TestRoot::new()).is_err());
+    }
+
+    #[test]
+    fn reports_unreadable_lsb_sources() {
+        let root = TestRoot::new().with("var/lib/dpkg/status/not-a-file", "");
+
+        assert!(lsb_version_in_root(root.path()).is_err());
+        assert_eq!(lsb_version_in_root(TestRoot::new().path()).unwrap(), None);
+    }
+
+    fn modules(modules: &[&str]) -> Vec<LsbModule> {
+        modules
+            .iter()
+            .map(|module| module.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn reads_modules_from_provides() {
+        let installed = [
+            LsbPackage {
+                lsb_version: "4.1".to_string(),
+                provides: "lsb-core-kfreebsd-amd64 (= 4.0), lsb-toolkit-gtk-amd64,\n\
+                           lsb-security-noarch, liblsb-core-amd64, lsb-core-amd64 (= 4.1) junk"
+                    .to_string(),
+            },
+            LsbPackage {
+                lsb_version: "5.0".to_string(),
+                provides: "lsb-desktop-amd64 (= 5.0)".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            lsb_modules(&installed, &LsbCompatTable::embedded()),
+            Some(modules(&[
+                "core-4.0-kfreebsd-amd64",
+                "desktop-5.0-amd64",
+                "security-4.0-noarch",
+                "security-4.1-noarch",
+                "toolkit-gtk-3.1-amd64",
+                "toolkit-gtk-3.2-amd64",
+                "toolkit-gtk-4.0-amd64",
+                "toolkit-gtk-4.1-amd64",
+            ]))
+        );
+        assert_eq!(lsb_modules(&[], &LsbCompatTable::embedded()), None);
+    }
+
+    #[test]
+    fn reads_the_compat_table_of_the_admindir_root() {
+        let root = TestRoot::new()
+            .with(
+                "var/lib/dpkg/status",
+                "Package: lsb-core\nStatus: install ok installed\nVersion: 4.1+Debian13\n\
+                 Provides: lsb-core-amd64\n",
+            )
+            .with(
+                "usr/share/lsb-release/module-compat.csv",
+                "version,module,compatible\n4.1,core,4.1\n",
+            );
+
+        assert_eq!(
+            lsb_version_in(root.path().join("var/lib/dpkg")).unwrap(),
+            Some(modules(&["core-4.1-amd64"]))
+        );
+        assert_eq!(
+            lsb_version_in_root(root.path()).unwrap(),
+            Some(modules(&["core-4.1-amd64"]))
+        );
+    }
+
+    const UBUNTU_CSV: &str = "version,codename,series,created,release,eol\n\
+                              22.04 LTS,Jammy Jellyfish,jammy,2021-10-21,2022-04-21,2027-06-01\n";
+
+    /// A root with the Debian and Ubuntu distro-info tables and the given `os-release`.
+    fn upstream_root(os_release: &str) -> TestRoot {
+        TestRoot::new()
+            .with(
+                "usr/share/distro-info/debian.csv",
+                crate::test_info::DEBIAN_CSV,
+            )
+            .with("usr/share/distro-info/ubuntu.csv", UBUNTU_CSV)
+            .with("etc/os-release", os_release)
+    }
+
+    fn detect_upstream(root: &TestRoot) -> Result<DistroInfo, LsbError> {
+        DistroInfo::detect_upstream(&PathGetter::new(root.path()))
+    }
+
+    #[test]
+    fn prefers_the_upstream_lsb_release() {
+        let root =
+            upstream_root("ID=linuxmint\nID_LIKE=\"ubuntu debian\"\nUBUNTU_CODENAME=jammy\n").with(
+                "etc/upstream-release/lsb-release",
+                "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=22.04\nDISTRIB_CODENAME=jammy\n\
+                 DISTRIB_DESCRIPTION=\"Ubuntu Jammy Jellyfish\"\n",
+            );
+
+        assert_eq!(
+            detect_upstream(&root).unwrap(),
+            info("Ubuntu", "Ubuntu Jammy Jellyfish", "22.04", "jammy")
+        );
+    }
+
+    #[test]
+    fn guesses_the_upstream_from_id_like() {
+        let mint = upstream_root(
+            "ID=linuxmint\nID_LIKE=\"ubuntu debian\"\nVERSION_CODENAME=virginia\n\
+             UBUNTU_CODENAME=jammy\n",
+        );
+        let lmde = upstream_root(
+            "ID=linuxmint\nID_LIKE=\"ubuntu debian\"\nVERSION_CODENAME=faye\n\
+             DEBIAN_CODENAME=bookworm\n",
+        );
+
+        assert_eq!(
+            detect_upstream(&mint).unwrap(),
+            info("Ubuntu", "Ubuntu 22.04 LTS", "22.04", "jammy")
+        );
+        // Ubuntu has no faye, so Debian is tried next
+        assert_eq!(
+            detect_upstream(&lmde).unwrap(),
+            info("Debian", "Debian 12", "12", "bookworm")
+        );
+    }
+
+    #[test]
+    fn completes_the_upstream_lsb_release_from_id_like() {
+        let root = upstream_root("ID=linuxmint\nID_LIKE=debian\nDEBIAN_CODENAME=bookworm\n").with(
+            "etc/upstream-release/lsb-release",
+            "DISTRIB_DESCRIPTION=\"Debian GNU/Linux 12\"\n",
+        );
+
+        assert_eq!(
+            detect_upstream(&root).unwrap(),
+            info("Debian", "Debian GNU/Linux 12", "12", "bookworm")
+        );
+    }
+
+    #[test]
+    fn reports_an_unknown_upstream() {
+        let unknown =
+            upstream_root("ID=linuxmint\nID_LIKE=\"ubuntu debian\"\nVERSION_CODENAME=faye\n");
+        let partial = upstream_root("ID=linuxmint\nID_LIKE=debian\nVERSION_CODENAME=faye\n")
+            .with("etc/upstream-release/lsb-release", "DISTRIB_ID=Debian\n");
+
+        assert!(matches!(
+            detect_upstream(&unknown),
+            Err(LsbError::NotDetected(_))
+        ));
+        assert_eq!(
+            detect_upstream(&partial).unwrap(),
+            DistroInfo {
+                id: Some("Debian".to_string()),
+                ..DistroInfo::default()
+            }
+        );
+        assert!(detect_upstream(&TestRoot::new()).is_err());
+    }
 }
@@ -0,0 +1,720 @@
+use crate::lsb_release::error::LsbError;
+use crate::lsb_release::imp::deb_version::DebVersion;
+use crate::lsb_release::imp::paths::PathGetter;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// One row of a distro-info table, such as `/usr/share/distro-info/debian.csv`.
+///
+/// Dates which the table leaves empty, such as the end of life of a supported release, are `None`.
+/// Columns only one of the Debian and Ubuntu tables has are `None` in the other.
+#[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub struct DistroRelease {
+    /// Version number, such as `12` or `22.04 LTS`. Development suites such as sid have none.
+    pub version: Option<String>,
+    /// Full codename, such as `Bookworm` or `Jammy Jellyfish`.
+    pub codename: String,
+    /// Short lowercase codename used for suites, such as `bookworm` or `jammy`.
+    pub series: String,
+    /// When development of the release started.
+    pub created: Option<NaiveDate>,
+    /// When the release was published.
+    pub release: Option<NaiveDate>,
+    /// End of regular security support.
+    pub eol: Option<NaiveDate>,
+    /// End of the Debian LTS project's support.
+    #[serde(rename = "eol-lts")]
+    pub eol_lts: Option<NaiveDate>,
+    /// End of the Debian Extended LTS offering.
+    #[serde(rename = "eol-elts")]
+    pub eol_elts: Option<NaiveDate>,
+    /// End of Ubuntu's support for server installations, if it differs from `eol`.
+    #[serde(rename = "eol-server")]
+    pub eol_server: Option<NaiveDate>,
+    /// End of Ubuntu Expanded Security Maintenance.
+    #[serde(rename = "eol-esm")]
+    pub eol_esm: Option<NaiveDate>,
+    /// End of Ubuntu's Legacy add-on.
+    #[serde(rename = "eol-legacy")]
+    pub eol_legacy: Option<NaiveDate>,
+}
+
+impl DistroRelease {
+    /// The version number without qualifiers, such as `22.04` of `22.04 LTS`.
+    #[must_use]
+    pub fn version_number(&self) -> Option<&str> {
+        self.version
+            .as_deref()
+            .and_then(|version| version.split_whitespace().next())
+    }
+
+    /// Whether this is an Ubuntu long-term support release, such as `22.04 LTS`.
+    #[must_use]
+    pub fn is_lts(&self) -> bool {
+        self.version
+            .as_deref()
+            .is_some_and(|version| version.ends_with(" LTS"))
+    }
+
+    fn is_supported(&self, date: NaiveDate) -> bool {
+        self.eol.is_none_or(|eol| date <= eol) || self.eol_server.is_some_and(|eol| date <= eol)
+    }
+
+    /// Reads every row of the distro-info table at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a distro-info table.
+    pub fn read_all(path: impl AsRef<Path>) -> Result<Vec<Self>, LsbError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(LsbError::io(path))?;
+
+        Self::parse_all(file, path.display().to_string())
+    }
+
+    fn parse_all(data: impl Read, context: String) -> Result<Vec<Self>, LsbError> {
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(data);
+        let headers = reader
+            .headers()
+            .map_err(LsbError::csv(context.clone()))?
+            .clone();
+
+        reader
+            .records()
+            .map(|record| {
+                // rows of releases which are still supported leave out the trailing empty dates
+                let mut record = record?;
+                while record.len() < headers.len() {
+                    record.push_field("");
+                }
+
+                record.deserialize(Some(&headers))
+            })
+            .collect::<Result<_, _>>()
+            .map_err(LsbError::csv(context))
+    }
+
+    /// Tells how the release is supported on `date`.
+    ///
+    /// The support phases are tried in the order regular, Ubuntu server, Debian LTS, Debian ELTS,
+    /// Ubuntu ESM and Ubuntu Legacy; the first which has not ended yet is reported. A phase still
+    /// lasts on its end date, as for the `--supported` queries of `distro-info`.
+    #[must_use]
+    pub fn support_status(&self, date: NaiveDate) -> SupportStatus {
+        let status = |phase, until: Option<NaiveDate>| SupportStatus {
+            phase,
+            until,
+            days_remaining: until.map(|until| (until - date).num_days()),
+        };
+
+        if self.release.is_none_or(|release| date < release) {
+            return status(SupportPhase::Unreleased, None);
+        }
+        if self.eol.is_none() {
+            return status(SupportPhase::Supported, None);
+        }
+
+        let phases = [
+            (SupportPhase::Supported, self.eol),
+            (SupportPhase::Supported, self.eol_server),
+            (SupportPhase::Lts, self.eol_lts),
+            (SupportPhase::Elts, self.eol_elts),
+            (SupportPhase::Esm, self.eol_esm),
+            (SupportPhase::Legacy, self.eol_legacy),
+        ];
+        let ends = phases
+            .into_iter()
+            .filter_map(|(phase, end)| Some((phase, end?)));
+
+        ends.clone().find(|(_, end)| date <= *end).map_or_else(
+            || status(SupportPhase::EndOfLife, ends.map(|(_, end)| end).max()),
+            |(phase, end)| status(phase, Some(end)),
+        )
+    }
+}
+
+/// The distro-info tables built into the crate, by origin.
+const EMBEDDED_TABLES: [(&str, &str); 2] = [
+    (
+        "debian",
+        include_str!("../../../data/distro-info/debian.csv"),
+    ),
+    (
+        "ubuntu",
+        include_str!("../../../data/distro-info/ubuntu.csv"),
+    ),
+];
+
+/// Where distro-info tables are read from.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+#[non_exhaustive]
+#[allow(clippy::module_name_repetitions)]
+pub enum DistroInfoSource {
+    /// `/usr/share/distro-info` if the table is there, so that updates of the distro-info-data
+    /// package are picked up, and the tables built into the crate otherwise.
+    #[default]
+    Auto,
+    /// Only `/usr/share/distro-info`.
+    System,
+    /// Only the tables built into the crate. See [`DistroInfoTable::embedded`].
+    Embedded,
+}
+
+impl FromStr for DistroInfoSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "system" => Ok(Self::System),
+            "embedded" => Ok(Self::Embedded),
+            _ => Err(format!(
+                "unknown source `{s}` (expected one of auto, system, embedded)"
+            )),
+        }
+    }
+}
+
+/// A whole distro-info table, in the order of the file, which is from the oldest release to the
+/// newest.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct DistroInfoTable {
+    releases: Vec<DistroRelease>,
+}
+
+impl DistroInfoTable {
+    /// Wraps rows which were read or built elsewhere.
+    #[must_use]
+    pub const fn new(releases: Vec<DistroRelease>) -> Self {
+        Self { releases }
+    }
+
+    /// Reads the distro-info table at `path`. See [`DistroRelease::read_all`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a distro-info table.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, LsbError> {
+        DistroRelease::read_all(path).map(Self::new)
+    }
+
+    /// Parses a distro-info table from `data`, such as the contents of a file which was read
+    /// elsewhere.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` cannot be read or is not a distro-info table.
+    pub fn parse(data: impl Read) -> Result<Self, LsbError> {
+        DistroRelease::parse_all(data, "distro-info data".to_string()).map(Self::new)
+    }
+
+    /// Reads `/usr/share/distro-info/<origin>.csv`, such as `debian` or `ubuntu`, or the table
+    /// built into the crate if that file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no table for `origin` or it cannot be read.
+    pub fn load(origin: &str) -> Result<Self, LsbError> {
+        Self::load_in("/", origin)
+    }
+
+    /// Same as [`DistroInfoTable::load`], but for the system installed in `root`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no table for `origin` or it cannot be read.
+    pub fn load_in(root: impl Into<PathBuf>, origin: &str) -> Result<Self, LsbError> {
+        Self::load_with(&PathGetter::new(root), origin)
+    }
+
+    /// The table built into this crate for `origin`, if there is one; there are tables for
+    /// `debian` and `ubuntu`.
+    ///
+    /// They are the `data/distro-info/*.csv` files checked into this crate, which are copies of the
+    /// tables the `distro-info-data` package installs in `/usr/share/distro-info`. They are only as
+    /// recent as the crate release; to refresh them, copy those files from an up-to-date
+    /// `distro-info-data` over the ones in `data/distro-info` and rebuild.
+    #[must_use]
+    pub fn embedded(origin: &str) -> Option<Self> {
+        let (origin, data) = EMBEDDED_TABLES
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(origin))?;
+
+        DistroRelease::parse_all(data.as_bytes(), format!("the embedded {origin}.csv"))
+            .ok()
+            .map(Self::new)
+    }
+
+    /// Reads the table of `origin` from where `paths` says: `/usr/share/distro-info` in the root
+    /// or the embedded tables.
+    pub(in crate::lsb_release) fn load_with(
+        paths: &PathGetter,
+        origin: &str,
+    ) -> Result<Self, LsbError> {
+        let system = || paths.known_distro_info_csv(origin).map(Self::read);
+        let embedded = || Self::embedded(origin).map(Ok);
+
+        let table = match paths.distro_info_source() {
+            DistroInfoSource::Auto => system().or_else(embedded),
+            DistroInfoSource::System => system(),
+            DistroInfoSource::Embedded => embedded(),
+        };
+
+        table.unwrap_or_else(|| {
+            Err(LsbError::Io {
+                context: format!("the distro-info table of {origin}"),
+                source: io::ErrorKind::NotFound.into(),
+            })
+        })
+    }
+
+    /// Every row of the table.
+    #[must_use]
+    pub const fn releases(&self) -> &[DistroRelease] {
+        self.releases.as_slice()
+    }
+
+    /// Every row of the table, oldest version first. Development suites without a version number,
+    /// such as sid, come last.
+    #[must_use]
+    pub fn in_release_order(&self) -> Vec<&DistroRelease> {
+        let mut releases = self.releases.iter().collect::<Vec<_>>();
+        releases.sort_by_cached_key(|r| {
+            let version = r
+                .version_number()
+                .and_then(|version| version.parse::<DebVersion>().ok());
+            (version.is_none(), version)
+        });
+
+        releases
+    }
+
+    /// The releases to upgrade through, one at a time, to get from `from` to `to`, ending with
+    /// `to`. From an Ubuntu LTS release the path jumps straight to the next LTS release if that is
+    /// not past `to`, as `do-release-upgrade` does.
+    ///
+    /// The path is empty if `to` is not newer than `from` or either is not in the table.
+    #[must_use]
+    pub fn upgrade_path(&self, from: &DistroRelease, to: &DistroRelease) -> Vec<&DistroRelease> {
+        let order = self.in_release_order();
+        let position =
+            |release: &DistroRelease| order.iter().position(|r| r.series == release.series);
+        let (Some(mut current), Some(target)) = (position(from), position(to)) else {
+            return vec![];
+        };
+
+        let mut path = vec![];
+        while current < target {
+            let next_lts = || (current + 1..=target).find(|&candidate| order[candidate].is_lts());
+            current = order[current]
+                .is_lts()
+                .then(next_lts)
+                .flatten()
+                .unwrap_or(current + 1);
+            path.push(order[current]);
+        }
+
+        path
+    }
+
+    /// The release which is in testing on `date`: the newest one which was created but not yet
+    /// released by then.
+    ///
+    /// Development suites without a version number, such as sid and experimental, are never
+    /// considered.
+    #[must_use]
+    pub fn testing(&self, date: NaiveDate) -> Option<&DistroRelease> {
+        self.releases
+            .iter()
+            .filter(|r| r.version.is_some())
+            .filter(|r| r.created.is_some_and(|created| created <= date))
+            .filter(|r| r.release.is_none_or(|release| date < release))
+            .max_by_key(|r| r.created)
+    }
+
+    /// The releases which exist on `date` and whose regular support has not ended, including
+    /// development suites, like `distro-info --supported`. Ubuntu releases whose server support
+    /// lasts longer count as supported until that ends.
+    #[must_use]
+    pub fn supported(&self, date: NaiveDate) -> Vec<&DistroRelease> {
+        self.created_by(date)
+            .filter(|r| r.is_supported(date))
+            .collect()
+    }
+
+    /// The releases which exist on `date` but are not [supported](DistroInfoTable::supported),
+    /// like `distro-info --unsupported`.
+    #[must_use]
+    pub fn unsupported(&self, date: NaiveDate) -> Vec<&DistroRelease> {
+        self.created_by(date)
+            .filter(|r| !r.is_supported(date))
+            .collect()
+    }
+
+    fn created_by(&self, date: NaiveDate) -> impl Iterator<Item = &DistroRelease> {
+        self.releases
+            .iter()
+            .filter(move |r| r.created.is_some_and(|created| created <= date))
+    }
+
+    /// The release whose version number is `version`, such as `12` or `22.04`.
+    ///
+    /// A point release matches its major release if the table only lists that, so `12.4` finds
+    /// Debian 12.
+    #[must_use]
+    pub fn by_version(&self, version: &str) -> Option<&DistroRelease> {
+        let numbers = || {
+            self.releases
+                .iter()
+                .filter_map(|r| Some((r.version_number()?, r)))
+        };
+
+        numbers()
+            .find(|(number, _)| *number == version)
+            .or_else(|| {
+                numbers().find(|(number, _)| version.starts_with(format!("{number}.").as_str()))
+            })
+            .map(|(_, r)| r)
+    }
+
+    /// The release whose series is `series`, such as `bookworm`, ignoring case.
+    #[must_use]
+    pub fn by_series(&self, series: &str) -> Option<&DistroRelease> {
+        self.releases
+            .iter()
+            .find(|r| r.series.eq_ignore_ascii_case(series))
+    }
+
+    /// The release whose full codename is `codename`, such as `Jammy Jellyfish`, ignoring case.
+    #[must_use]
+    pub fn by_codename(&self, codename: &str) -> Option<&DistroRelease> {
+        self.releases
+            .iter()
+            .find(|r| r.codename.eq_ignore_ascii_case(codename))
+    }
+
+    /// The release an alias refers to on `date`.
+    ///
+    /// The aliases are `stable` (the newest published release), `oldstable`, `oldoldstable`,
+    /// `testing` (see [`DistroInfoTable::testing`]) and `unstable` (sid).
+    #[must_use]
+    pub fn by_alias(&self, alias: &str, date: NaiveDate) -> Option<&DistroRelease> {
+        let nth_stable = |n| {
+            let mut released = self
+                .releases
+                .iter()
+                .filter(|r| r.version.is_some())
+                .filter(|r| r.release.is_some_and(|release| release <= date))
+                .collect::<Vec<_>>();
+            released.sort_by_key(|r| Reverse(r.release));
+            released.get(n).copied()
+        };
+
+        match alias {
+            "stable" => nth_stable(0),
+            "oldstable" => nth_stable(1),
+            "oldoldstable" => nth_stable(2),
+            "testing" => self.testing(date),
+            "unstable" => self.by_series("sid"),
+            _ => None,
+        }
+    }
+
+    /// Finds a release by any of its names: an alias as of `date`, its series, its full codename
+    /// or its version number, tried in this order.
+    #[must_use]
+    pub fn lookup(&self, name: &str, date: NaiveDate) -> Option<&DistroRelease> {
+        self.by_alias(name, date)
+            .or_else(|| self.by_series(name))
+            .or_else(|| self.by_codename(name))
+            .or_else(|| self.by_version(name))
+    }
+}
+
+/// Which kind of support a release gets on a given date. See [`DistroRelease::support_status`].
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum SupportPhase {
+    /// The release has not been published yet, like Debian testing.
+    Unreleased,
+    /// The release gets regular security support.
+    Supported,
+    /// Regular support ended; the Debian LTS project still supports it.
+    Lts,
+    /// Debian LTS ended; the Extended LTS offering still supports it.
+    Elts,
+    /// Regular support ended; Ubuntu Expanded Security Maintenance still supports it.
+    Esm,
+    /// ESM ended; Ubuntu's Legacy add-on still supports it.
+    Legacy,
+    /// Every kind of support ended.
+    EndOfLife,
+}
+
+impl Display for SupportPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unreleased => "unreleased",
+            Self::Supported => "supported",
+            Self::Lts => "lts",
+            Self::Elts => "elts",
+            Self::Esm => "esm",
+            Self::Legacy => "legacy",
+            Self::EndOfLife => "end-of-life",
+        })
+    }
+}
+
+/// How a release is supported on a given date.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+#[non_exhaustive]
+pub struct SupportStatus {
+    /// The current kind of support.
+    pub phase: SupportPhase,
+    /// When the current phase ends, or when the last one ended for [`SupportPhase::EndOfLife`].
+    /// `None` if no date is announced.
+    pub until: Option<NaiveDate>,
+    /// Days until `until`; negative once it has passed.
+    pub days_remaining: Option<i64>,
+}
+
+impl Display for SupportStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.phase, self.until, self.days_remaining) {
+            (SupportPhase::EndOfLife, Some(until), Some(days)) => {
+                write!(f, "end-of-life since {until} ({} days ago)", -days)
+            }
+            (phase, Some(until), Some(days)) => {
+                write!(f, "{phase} until {until} ({days} days left)")
+            }
+            (SupportPhase::Unreleased, _, _) => write!(f, "unreleased"),
+            (phase, _, _) => write!(f, "{phase} (no end of life announced)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::DEBIAN_CSV;
+
+    const UBUNTU: &str = "\
+version,codename,series,created,release,eol,eol-server,eol-esm,eol-legacy
+10.04 LTS,Lucid Lynx,lucid,2009-10-29,2010-04-29,2013-05-09,2015-04-30
+";
+
+    const UBUNTU_SERIES: &str = "\
+version,codename,series,created,release,eol,eol-server,eol-esm
+20.04 LTS,Focal Fossa,focal,2019-10-17,2020-04-23,2025-05-29,2025-05-29,2030-04-23
+20.10,Groovy Gorilla,groovy,2020-04-23,2020-10-22,2021-07-22
+21.04,Hirsute Hippo,hirsute,2020-10-22,2021-04-22,2022-01-20
+21.10,Impish Indri,impish,2021-04-22,2021-10-14,2022-07-14
+22.04 LTS,Jammy Jellyfish,jammy,2021-10-14,2022-04-21,2027-06-01,2027-06-01,2032-04-21
+22.10,Kinetic Kudu,kinetic,2022-04-21,2022-10-20,2023-07-20
+24.04 LTS,Noble Numbat,noble,2023-10-12,2024-04-25,2029-05-31,2029-05-31,2034-04-25
+23.10,Mantic Minotaur,mantic,2023-04-20,2023-10-12,2024-07-11
+";
+
+    fn releases(csv: &str) -> Vec<DistroRelease> {
+        DistroInfoTable::parse(csv.as_bytes())
+            .unwrap()
+            .releases()
+            .to_vec()
+    }
+
+    fn series_to_upgrade_through<'t>(
+        table: &'t DistroInfoTable,
+        from: &str,
+        to: &str,
+    ) -> Vec<&'t str> {
+        let release = |series| table.by_series(series).unwrap();
+
+        table
+            .upgrade_path(release(from), release(to))
+            .into_iter()
+            .map(|r| r.series.as_str())
+            .collect()
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn phase_on(release: &DistroRelease, day: &str) -> (SupportPhase, Option<NaiveDate>) {
+        let status = release.support_status(date(day));
+        (status.phase, status.until)
+    }
+
+    #[test]
+    fn walks_through_the_debian_support_phases() {
+        let bullseye = &releases(DEBIAN_CSV)[0];
+
+        assert_eq!(
+            phase_on(bullseye, "2021-08-13"),
+            (SupportPhase::Unreleased, None)
+        );
+        assert_eq!(
+            phase_on(bullseye, "2021-08-14"),
+            (SupportPhase::Supported, Some(date("2024-08-14")))
+        );
+        assert_eq!(
+            phase_on(bullseye, "2024-08-14"),
+            (SupportPhase::Supported, Some(date("2024-08-14")))
+        );
+        assert_eq!(
+            phase_on(bullseye, "2024-08-15"),
+            (SupportPhase::Lts, Some(date("2026-08-31")))
+        );
+        assert_eq!(
+            phase_on(bullseye, "2026-09-01"),
+            (SupportPhase::Elts, Some(date("2031-06-30")))
+        );
+        assert_eq!(
+            phase_on(bullseye, "2031-07-01"),
+            (SupportPhase::EndOfLife, Some(date("2031-06-30")))
+        );
+        assert_eq!(
+            bullseye.support_status(date("2031-07-11")).days_remaining,
+            Some(-11)
+        );
+    }
+
+    #[test]
+    fn keeps_ubuntu_servers_supported_longer() {
+        let lucid = &releases(UBUNTU)[0];
+
+        assert_eq!(
+            phase_on(lucid, "2013-05-09"),
+            (SupportPhase::Supported, Some(date("2013-05-09")))
+        );
+        assert_eq!(
+            phase_on(lucid, "2013-05-10"),
+            (SupportPhase::Supported, Some(date("2015-04-30")))
+        );
+        assert_eq!(
+            phase_on(lucid, "2015-05-01"),
+            (SupportPhase::EndOfLife, Some(date("2015-04-30")))
+        );
+    }
+
+    #[test]
+    fn agrees_with_is_supported() {
+        for release in releases(DEBIAN_CSV).iter().chain(&releases(UBUNTU)) {
+            for day in [
+                "2013-05-10",
+                "2015-04-30",
+                "2024-08-14",
+                "2026-07-11",
+                "2040-01-01",
+            ] {
+                let phase = release.support_status(date(day)).phase;
+                let is_released = release.release.is_some_and(|r| r <= date(day));
+
+                assert_eq!(
+                    phase == SupportPhase::Supported,
+                    is_released && release.is_supported(date(day)),
+                    "{} on {day}",
+                    release.series
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn has_no_end_without_an_eol() {
+        let releases = releases(DEBIAN_CSV);
+
+        assert_eq!(
+            phase_on(&releases[2], "2030-01-01"),
+            (SupportPhase::Supported, None)
+        );
+        assert_eq!(
+            phase_on(&releases[3], "2030-01-01"),
+            (SupportPhase::Unreleased, None)
+        );
+    }
+
+    #[test]
+    fn looks_releases_up_by_any_name() {
+        let table = DistroInfoTable::new(releases(DEBIAN_CSV));
+        let series = |release: Option<&DistroRelease>| release.map(|r| r.series.clone());
+        let day = date("2024-01-01");
+
+        assert_eq!(
+            series(table.lookup("stable", day)),
+            Some("bookworm".to_string())
+        );
+        assert_eq!(
+            series(table.lookup("oldstable", day)),
+            Some("bullseye".to_string())
+        );
+        assert_eq!(series(table.lookup("oldoldstable", day)), None);
+        assert_eq!(
+            series(table.lookup("testing", day)),
+            Some("trixie".to_string())
+        );
+        assert_eq!(
+            series(table.lookup("unstable", day)),
+            Some("sid".to_string())
+        );
+        assert_eq!(
+            series(table.lookup("Bookworm", day)),
+            Some("bookworm".to_string())
+        );
+        assert_eq!(
+            series(table.lookup("12", day)),
+            Some("bookworm".to_string())
+        );
+        assert_eq!(
+            series(table.lookup("12.4", day)),
+            Some("bookworm".to_string())
+        );
+        assert_eq!(series(table.lookup("1", day)), None);
+        assert_eq!(series(table.lookup("buster", day)), None);
+        assert_eq!(
+            series(table.lookup("stable", date("2025-08-09"))),
+            Some("trixie".to_string())
+        );
+    }
+
+    #[test]
+    fn plans_upgrades_one_release_at_a_time() {
+        let table = DistroInfoTable::new(releases(DEBIAN_CSV));
+        let path = |from, to| series_to_upgrade_through(&table, from, to);
+
+        assert_eq!(path("bullseye", "trixie"), ["bookworm", "trixie"]);
+        assert_eq!(path("bookworm", "sid"), ["trixie", "forky", "sid"]);
+        assert!(path("trixie", "bullseye").is_empty());
+        assert!(path("trixie", "trixie").is_empty());
+    }
+
+    #[test]
+    fn jumps_from_lts_to_lts() {
+        let table = DistroInfoTable::new(releases(UBUNTU_SERIES));
+        let path = |from, to| series_to_upgrade_through(&table, from, to);
+
+        assert_eq!(
+            table
+                .in_release_order()
+                .iter()
+                .map(|r| r.series.as_str())
+                .collect::<Vec<_>>(),
+            ["focal", "groovy", "hirsute", "impish", "jammy", "kinetic", "mantic", "noble"]
+        );
+        assert_eq!(path("focal", "noble"), ["jammy", "noble"]);
+        assert_eq!(path("focal", "mantic"), ["jammy", "kinetic", "mantic"]);
+        assert_eq!(path("focal", "hirsute"), ["groovy", "hirsute"]);
+        assert_eq!(
+            path("groovy", "kinetic"),
+            ["hirsute", "impish", "jammy", "kinetic"]
+        );
+    }
+}
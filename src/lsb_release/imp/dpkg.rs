@@ -0,0 +1,110 @@
+use crate::lsb_release::imp::deb822::{parse_paragraphs, Paragraph};
+use std::fs::read_to_string;
+use std::io;
+use std::path::Path;
+
+/// The `status` file of a dpkg database, read without running `dpkg-query`.
+pub(in crate::lsb_release::imp) struct DpkgStatus {
+    packages: Vec<Paragraph>,
+}
+
+/// What `dpkg-query -f '${Version} ${Provides}' -W` prints for an installed package.
+pub(in crate::lsb_release::imp) struct InstalledPackage {
+    pub(in crate::lsb_release::imp) version: String,
+    pub(in crate::lsb_release::imp) provides: String,
+}
+
+impl DpkgStatus {
+    /// Reads `status` in `admindir`, which is `/var/lib/dpkg` unless dpkg is told otherwise with
+    /// `--admindir`.
+    pub(in crate::lsb_release::imp) fn load(admindir: &Path) -> io::Result<Self> {
+        let text = read_to_string(admindir.join("status"))?;
+
+        Ok(Self {
+            packages: parse_paragraphs(text.as_str()),
+        })
+    }
+
+    /// Returns every installed instance of `package`; there is one per architecture for
+    /// `Multi-Arch: same` packages.
+    pub(in crate::lsb_release::imp) fn installed(&self, package: &str) -> Vec<InstalledPackage> {
+        self.packages
+            .iter()
+            .filter(|paragraph| paragraph.get("Package") == Some(package))
+            .filter(|paragraph| {
+                // `Status: want flag state`; only the state tells whether it is on disk
+                paragraph
+                    .get("Status")
+                    .and_then(|status| status.split_whitespace().nth(2))
+                    .is_some_and(|state| !["not-installed", "config-files"].contains(&state))
+            })
+            .filter_map(|paragraph| {
+                Some(InstalledPackage {
+                    version: paragraph.get("Version")?.to_string(),
+                    provides: paragraph.get("Provides").unwrap_or_default().to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    const STATUS: &str = "\
+Package: lsb-core
+Status: install ok installed
+Architecture: amd64
+Version: 11.1.0
+Provides: lsb-core-amd64 (= 5.0),
+ lsb-core-noarch (= 5.0)
+
+Package: lsb-core
+Status: install ok installed
+Architecture: i386
+Version: 11.1.0
+
+Package: lsb-desktop
+Status: deinstall ok config-files
+Version: 11.1.0
+
+Package: lsb-printing
+Status: purge ok not-installed
+
+Package: lsb-security
+Status: install ok unpacked
+Version: 11.1.0
+";
+
+    #[test]
+    fn lists_every_installed_instance() {
+        let root = TestRoot::new().with("status", STATUS);
+        let status = DpkgStatus::load(root.path()).unwrap();
+
+        let core = status.installed("lsb-core");
+        assert_eq!(core.len(), 2);
+        assert_eq!(core[0].version, "11.1.0");
+        assert_eq!(
+            core[0].provides,
+            "lsb-core-amd64 (= 5.0),\nlsb-core-noarch (= 5.0)"
+        );
+        assert_eq!(core[1].provides, "");
+
+        assert_eq!(status.installed("lsb-security").len(), 1);
+        assert!(status.installed("lsb-desktop").is_empty());
+        assert!(status.installed("lsb-printing").is_empty());
+        assert!(status.installed("lsb").is_empty());
+    }
+
+    #[test]
+    fn fails_without_a_status_file() {
+        let root = TestRoot::new();
+
+        assert_eq!(
+            DpkgStatus::load(root.path()).err().map(|e| e.kind()),
+            Some(io::ErrorKind::NotFound)
+        );
+    }
+}
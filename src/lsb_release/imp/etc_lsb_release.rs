@@ -0,0 +1,81 @@
+use crate::lsb_release::imp::dist::DistroInfo;
+use crate::lsb_release::imp::lsb_module::LsbModule;
+use crate::lsb_release::imp::os_release::parse_assignment;
+use std::fs::read_to_string;
+use std::io;
+use std::path::Path;
+
+/// The contents of `/etc/lsb-release`, as read by `get_lsb_information()` of the Python
+/// `lsb_release`.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub(in crate::lsb_release) struct EtcLsbRelease {
+    /// `DISTRIB_ID`, `DISTRIB_RELEASE`, `DISTRIB_CODENAME` and `DISTRIB_DESCRIPTION`.
+    pub(in crate::lsb_release) info: DistroInfo,
+    /// `LSB_VERSION`, a `:`-separated list of modules such as `core-4.1-amd64`. Entries which are
+    /// not modules are skipped.
+    pub(in crate::lsb_release) lsb_version: Option<Vec<LsbModule>>,
+}
+
+impl EtcLsbRelease {
+    pub(in crate::lsb_release) fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut lsb_release = Self::default();
+
+        for (name, value) in read_to_string(path)?.lines().filter_map(parse_assignment) {
+            let value = value.trim().to_string();
+            // empty values are ignored, as in the Python implementation
+            if value.is_empty() {
+                continue;
+            }
+
+            match name {
+                "DISTRIB_ID" => lsb_release.info.id = Some(value),
+                "DISTRIB_RELEASE" => lsb_release.info.release = Some(value),
+                "DISTRIB_CODENAME" => lsb_release.info.codename = Some(value),
+                "DISTRIB_DESCRIPTION" => lsb_release.info.description = Some(value),
+                "LSB_VERSION" => {
+                    lsb_release.lsb_version = Some(
+                        value
+                            .split(':')
+                            .filter_map(|module| module.parse().ok())
+                            .collect(),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        Ok(lsb_release)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    #[test]
+    fn reads_the_distrib_variables() {
+        let root = TestRoot::new().with(
+            "etc/lsb-release",
+            "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=22.04\nDISTRIB_CODENAME=\n\
+             DISTRIB_DESCRIPTION=\"Ubuntu 22.04.3 LTS\"\n\
+             LSB_VERSION=core-4.1-amd64:not-a-module:security-4.1-noarch\n",
+        );
+        let lsb_release = EtcLsbRelease::read(root.path().join("etc/lsb-release")).unwrap();
+
+        assert_eq!(lsb_release.info.id.as_deref(), Some("Ubuntu"));
+        assert_eq!(lsb_release.info.release.as_deref(), Some("22.04"));
+        assert_eq!(lsb_release.info.codename, None);
+        assert_eq!(
+            lsb_release.info.description.as_deref(),
+            Some("Ubuntu 22.04.3 LTS")
+        );
+        assert_eq!(
+            lsb_release.lsb_version,
+            Some(vec![
+                LsbModule::new("core", "4.1", "amd64"),
+                LsbModule::new("security", "4.1", "noarch")
+            ])
+        );
+    }
+}
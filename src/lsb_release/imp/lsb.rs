@@ -1,38 +1,192 @@
-pub(in crate::lsb_release::imp) fn valid_lsb_versions<'v: 'r, 'r>(
-    version: &'v str,
-    module: &'r str,
-) -> Vec<&'r str> {
-    match version {
-        "3.0" => &["2.0", "3.0"] as &[&str],
-        "3.1" => match module {
-            "desktop" | "qt4" => &["3.1"] as &[&str],
-            "cxx" => &["3.0"],
-            _ => &["2.0", "3.0", "3.1"],
-        },
-        "3.2" => match module {
-            "desktop" => &["3.1", "3.2"] as &[&str],
-            "qt4" => &["3.1"],
-            "printing" | "languages" | "multimedia" => &["3.2"],
-            "cxx" => &["3.0", "3.1", "3.2"],
-            _ => &["2.0", "3.0", "3.1", "3.2"],
-        },
-        "4.0" => match module {
-            "desktop" => &["3.1", "3.2", "4.0"] as &[&str],
-            "qt4" => &["3.1"],
-            "printing" | "languages" | "multimedia" => &["3.2", "4.0"],
-            "security" => &["4.0"],
-            "cxx" => &["3.0", "3.1", "3.2", "4.0"],
-            _ => &["2.0", "3.0", "3.1", "3.2", "4.0"],
-        },
-        "4.1" => match module {
-            "desktop" => &["3.1", "3.2", "4.0", "4.1"] as &[&str],
-            "qt4" => &["3.1"],
-            "printing" | "languages" | "multimedia" => &["3.2", "4.0", "4.1"],
-            "security" => &["4.0", "4.1"],
-            "cxx" => &["3.0", "3.1", "3.2", "4.0", "4.1"],
-            _ => &["2.0", "3.0", "3.1", "3.2", "4.0", "4.1"],
-        },
-        _ => return vec![version],
-    }
-    .to_vec()
+use crate::lsb_release::error::LsbError;
+use crate::lsb_release::imp::paths::PathGetter;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// The table built into the crate, parsed on first use.
+static EMBEDDED_TABLE: Lazy<LsbCompatTable> = Lazy::new(|| {
+    LsbCompatTable::parse(
+        include_bytes!("../../../data/lsb/module-compat.csv").as_slice(),
+        "the embedded module-compat.csv".to_string(),
+    )
+    .expect("the embedded LSB module table is valid")
+});
+
+/// An installed package which provides LSB modules, whichever package manager it comes from.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub(in crate::lsb_release::imp) struct LsbPackage {
+    /// The LSB version the package implements, such as `4.1`; unversioned provides are of it.
+    pub(in crate::lsb_release::imp) lsb_version: String,
+    /// The modules it provides, the way dpkg writes them, such as `lsb-core-amd64 (= 4.1)`.
+    pub(in crate::lsb_release::imp) provides: String,
+}
+
+#[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
+struct CompatRow {
+    version: String,
+    module: String,
+    /// Space-separated.
+    compatible: String,
+}
+
+/// Which older LSB versions a module of an LSB release also conforms to; an `lsb-core` package of
+/// LSB 4.1 provides `core-4.1-amd64` and also `core-4.0-amd64`, `core-3.2-amd64` and so on.
+///
+/// The table is a CSV file with the columns `version`, `module` and `compatible`, the last one
+/// being a space-separated list of versions. A `module` of `*` applies to every module of that
+/// version without a row of its own, and lines starting with `#` are comments:
+///
+/// ```text
+/// version,module,compatible
+/// 4.1,*,2.0 3.0 3.1 3.2 4.0 4.1
+/// 4.1,security,4.0 4.1
+/// ```
+///
+/// A table covering LSB 3.0 through 5.0 is built into the crate. Rows of
+/// `/usr/share/lsb-release/module-compat.csv`, or of the file `$LSB_MODULE_COMPAT` names, are
+/// added to it and take precedence, so that distributions can extend it without a rebuild.
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub struct LsbCompatTable {
+    rows: Vec<CompatRow>,
+}
+
+impl LsbCompatTable {
+    /// The table built into the crate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the built-in table is malformed, which only a broken build of this crate can cause.
+    #[must_use]
+    pub fn embedded() -> Self {
+        EMBEDDED_TABLE.clone()
+    }
+
+    /// Reads the table at `path`. See [`LsbCompatTable`] for the format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not such a table.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, LsbError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(LsbError::io(path))?;
+
+        Self::parse(file, path.display().to_string())
+    }
+
+    /// The built-in table, extended by the one of the running system if there is one.
+    #[must_use]
+    pub fn load() -> Self {
+        Self::load_with(&PathGetter::default())
+    }
+
+    /// The built-in table, extended by the one at the path `paths` gives if that can be read.
+    pub(in crate::lsb_release) fn load_with(paths: &PathGetter) -> Self {
+        Self::load_checked(paths).0
+    }
+
+    /// Same as `load_with`, but also tells why the table at the path `paths` gives was ignored, if
+    /// there is such a file.
+    pub(in crate::lsb_release) fn load_checked(paths: &PathGetter) -> (Self, Option<LsbError>) {
+        let embedded = Self::embedded();
+        let path = paths.lsb_module_compat();
+        if !path.exists() {
+            return (embedded, None);
+        }
+
+        match Self::read(path) {
+            Ok(system) => (embedded.extended_by(system), None),
+            Err(e) => (embedded, Some(e)),
+        }
+    }
+
+    fn parse(data: impl Read, context: String) -> Result<Self, LsbError> {
+        let rows = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_reader(data)
+            .deserialize()
+            .collect::<Result<_, _>>()
+            .map_err(LsbError::csv(context))?;
+
+        Ok(Self { rows })
+    }
+
+    /// Adds the rows of `other`, which replace the rows of this table for the same version and
+    /// module.
+    #[must_use]
+    pub fn extended_by(mut self, other: Self) -> Self {
+        self.rows.retain(|row| {
+            !other
+                .rows
+                .iter()
+                .any(|new| new.version == row.version && new.module == row.module)
+        });
+        self.rows.extend(other.rows);
+
+        self
+    }
+
+    /// The LSB versions the module `module` of LSB `version` conforms to, such as `4.0` and `4.1`
+    /// for `security` of `4.1`. A version the table does not know conforms only to itself.
+    #[must_use]
+    pub fn compatible_versions<'t>(&'t self, version: &'t str, module: &str) -> Vec<&'t str> {
+        let row = |module: &str| {
+            self.rows
+                .iter()
+                .find(|row| row.version == version && row.module == module)
+        };
+
+        row(module).or_else(|| row("*")).map_or_else(
+            || vec![version],
+            |row| row.compatible.split_whitespace().collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeds_lsb_3_to_5() {
+        let table = LsbCompatTable::embedded();
+
+        assert_eq!(
+            table.compatible_versions("4.1", "core"),
+            ["2.0", "3.0", "3.1", "3.2", "4.0", "4.1"]
+        );
+        assert_eq!(table.compatible_versions("4.1", "security"), ["4.0", "4.1"]);
+        for version in ["3.0", "3.1", "3.2", "4.0", "4.1", "5.0"] {
+            assert!(table
+                .compatible_versions(version, "core")
+                .contains(&version));
+        }
+        assert_eq!(table.compatible_versions("9.9", "core"), ["9.9"]);
+    }
+
+    #[test]
+    fn prefers_rows_of_the_system_table() {
+        let system = LsbCompatTable::parse(
+            b"# local rules\nversion,module,compatible\n4.1, security , 4.1\n9.9,*,9.0 9.9\n"
+                .as_slice(),
+            "a test table".to_string(),
+        )
+        .unwrap();
+        let table = LsbCompatTable::embedded().extended_by(system);
+
+        assert_eq!(table.compatible_versions("4.1", "security"), ["4.1"]);
+        assert_eq!(table.compatible_versions("9.9", "desktop"), ["9.0", "9.9"]);
+        assert_eq!(table.compatible_versions("4.1", "core").len(), 6);
+    }
+
+    #[test]
+    fn rejects_malformed_tables() {
+        assert!(
+            LsbCompatTable::parse(b"version,module\n4.1,core\n".as_slice(), String::new()).is_err()
+        );
+    }
 }
@@ -0,0 +1,131 @@
+use crate::lsb_release::error::LsbError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// An LSB module the system conforms to, such as `core-4.1-amd64`.
+///
+/// Both the name and the architecture may contain `-`, as in `toolkit-gtk-3.1-amd64` and
+/// `core-4.1-kfreebsd-amd64`, so a module is split at its version: the last `-`-separated part
+/// before the architecture which consists of digits and dots.
+///
+/// Modules sort by name, then version, then architecture, each compared as a string like the
+/// Python `lsb_release` does.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Debug)]
+#[non_exhaustive]
+#[allow(clippy::module_name_repetitions)]
+pub struct LsbModule {
+    /// Name of the module, such as `core` or `desktop`.
+    pub name: String,
+    /// Version of the LSB specification, such as `4.1`.
+    pub version: String,
+    /// Architecture, such as `amd64` or `noarch`.
+    pub arch: String,
+}
+
+impl LsbModule {
+    #[must_use]
+    pub fn new(name: &str, version: &str, arch: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            arch: arch.to_string(),
+        }
+    }
+
+    /// Joins `modules` with `:`, as in `LSB_VERSION` and the output of `-v`.
+    #[must_use]
+    pub fn join(modules: &[Self]) -> String {
+        modules
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+impl FromStr for LsbModule {
+    type Err = LsbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || LsbError::Parse {
+            context: format!("LSB module `{s}`"),
+            reason: "expected `<name>-<version>-<arch>`".to_string(),
+        };
+
+        let is_version = |part: &str| {
+            part.split('.')
+                .all(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+        };
+
+        let parts = s.split('-').collect::<Vec<_>>();
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(invalid());
+        }
+
+        // the name and the architecture each take at least one part
+        let version = (1..parts.len().saturating_sub(1))
+            .rev()
+            .find(|&i| is_version(parts[i]))
+            .ok_or_else(invalid)?;
+        let (name, arch) = (parts[..version].join("-"), parts[version + 1..].join("-"));
+
+        Ok(Self::new(name.as_str(), parts[version], arch.as_str()))
+    }
+}
+
+impl Display for LsbModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}-{}", self.name, self.version, self.arch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for (module, name, version, arch) in [
+            ("core-4.1-amd64", "core", "4.1", "amd64"),
+            ("toolkit-gtk-3.1-noarch", "toolkit-gtk", "3.1", "noarch"),
+            ("core-4.1-kfreebsd-amd64", "core", "4.1", "kfreebsd-amd64"),
+            ("core-11.1.0-noarch", "core", "11.1.0", "noarch"),
+            ("qt4-5-x86_64", "qt4", "5", "x86_64"),
+        ] {
+            let parsed = module.parse::<LsbModule>().unwrap();
+
+            assert_eq!(parsed, LsbModule::new(name, version, arch));
+            assert_eq!(parsed.to_string(), module);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_modules() {
+        for module in [
+            "",
+            "core",
+            "core-4.1",
+            "4.1-amd64",
+            "core-amd64",
+            "core-4.1-",
+            "-4.1-amd64",
+            "core--4.1-amd64",
+            "core-4.-amd64",
+            "core-v4.1-amd64",
+        ] {
+            assert!(module.parse::<LsbModule>().is_err(), "{module}");
+        }
+    }
+
+    #[test]
+    fn joins_with_colons() {
+        assert_eq!(
+            LsbModule::join(&[
+                LsbModule::new("core", "4.1", "amd64"),
+                LsbModule::new("core", "4.1", "noarch")
+            ]),
+            "core-4.1-amd64:core-4.1-noarch"
+        );
+        assert_eq!(LsbModule::join(&[]), "");
+    }
+}
@@ -0,0 +1,369 @@
+use crate::lsb_release::imp::paths::PathGetter;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The contents of an `os-release(5)` file.
+///
+/// Every field is `None` (or empty) if the file does not set it. Where the specification defines
+/// a default, such as `ID=linux`, it is not filled in. Variables which are not listed here, such
+/// as the vendor-specific `UBUNTU_CODENAME`, are kept in [`OsRelease::extra`].
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+#[non_exhaustive]
+pub struct OsRelease {
+    /// `NAME`, e.g. `Debian GNU/Linux`.
+    pub name: Option<String>,
+    /// `ID`, e.g. `debian`.
+    pub id: Option<String>,
+    /// `ID_LIKE`, e.g. `["ubuntu", "debian"]`.
+    pub id_like: Vec<String>,
+    /// `PRETTY_NAME`, e.g. `Debian GNU/Linux 12 (bookworm)`.
+    pub pretty_name: Option<String>,
+    /// `CPE_NAME`, a Common Platform Enumeration name.
+    pub cpe_name: Option<String>,
+    /// `VARIANT`, e.g. `Server Edition`.
+    pub variant: Option<String>,
+    /// `VARIANT_ID`, e.g. `server`.
+    pub variant_id: Option<String>,
+    /// `VERSION`, e.g. `12 (bookworm)`.
+    pub version: Option<String>,
+    /// `VERSION_ID`, e.g. `12`.
+    pub version_id: Option<String>,
+    /// `VERSION_CODENAME`, e.g. `bookworm`.
+    pub version_codename: Option<String>,
+    /// `BUILD_ID`, which identifies the build of an image.
+    pub build_id: Option<String>,
+    /// `IMAGE_ID`, which identifies an image.
+    pub image_id: Option<String>,
+    /// `IMAGE_VERSION`, the version of the image named by `IMAGE_ID`.
+    pub image_version: Option<String>,
+    /// `HOME_URL`.
+    pub home_url: Option<String>,
+    /// `DOCUMENTATION_URL`.
+    pub documentation_url: Option<String>,
+    /// `SUPPORT_URL`.
+    pub support_url: Option<String>,
+    /// `BUG_REPORT_URL`.
+    pub bug_report_url: Option<String>,
+    /// `PRIVACY_POLICY_URL`.
+    pub privacy_policy_url: Option<String>,
+    /// `SUPPORT_END`, a `YYYY-MM-DD` date.
+    pub support_end: Option<String>,
+    /// `LOGO`, an icon name.
+    pub logo: Option<String>,
+    /// `ANSI_COLOR`, e.g. `1;31`.
+    pub ansi_color: Option<String>,
+    /// `VENDOR_NAME`.
+    pub vendor_name: Option<String>,
+    /// `VENDOR_URL`.
+    pub vendor_url: Option<String>,
+    /// `DEFAULT_HOSTNAME`.
+    pub default_hostname: Option<String>,
+    /// `ARCHITECTURE`, e.g. `x86-64`.
+    pub architecture: Option<String>,
+    /// `SYSEXT_LEVEL`.
+    pub sysext_level: Option<String>,
+    /// `CONFEXT_LEVEL`.
+    pub confext_level: Option<String>,
+    /// `SYSEXT_SCOPE`, e.g. `["system", "portable"]`.
+    pub sysext_scope: Vec<String>,
+    /// `CONFEXT_SCOPE`.
+    pub confext_scope: Vec<String>,
+    /// `PORTABLE_PREFIXES`.
+    pub portable_prefixes: Vec<String>,
+    /// Every other variable, by name.
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Which `os-release` file is read.
+///
+/// `/etc/os-release` is preferred and `/usr/lib/os-release` is the fallback, unless the
+/// `LSB_OS_RELEASE` environment variable names a file.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub struct OsReleaseSource {
+    /// The file which is read.
+    pub path: PathBuf,
+    /// The fallback file, if it exists but is ignored although its contents differ from `path`.
+    pub shadowed: Option<PathBuf>,
+}
+
+impl Display for OsReleaseSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.shadowed {
+            None => write!(f, "os-release is read from {}", self.path.display()),
+            Some(ref shadowed) => write!(
+                f,
+                "os-release is read from {}, which takes precedence over {} although they differ",
+                self.path.display(),
+                shadowed.display()
+            ),
+        }
+    }
+}
+
+impl OsRelease {
+    /// Tells which file [`OsRelease::load`] reads.
+    #[must_use]
+    pub fn source() -> OsReleaseSource {
+        Self::source_in("/")
+    }
+
+    /// Same as [`OsRelease::source`], but for the system installed in `root`.
+    #[must_use]
+    pub fn source_in(root: impl Into<PathBuf>) -> OsReleaseSource {
+        PathGetter::new(root).os_release_source()
+    }
+
+    /// Reads the `os-release` file of the running system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn load() -> io::Result<Self> {
+        Self::load_in("/")
+    }
+
+    /// Same as [`OsRelease::load`], but for the system installed in `root`, such as a chroot or
+    /// a mounted image.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn load_in(root: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::read(PathGetter::new(root).lsb_os_release())
+    }
+
+    /// Reads and parses the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::parse(read_to_string(path)?.as_str()))
+    }
+
+    /// Parses the contents of an `os-release` file.
+    ///
+    /// Values follow shell quoting rules: they may be enclosed in single or double quotes, and
+    /// `$`, `` ` ``, `\` and `"` may be escaped with a backslash. Blank lines and comments are
+    /// skipped; so are invalid lines, such as ones without `=`, with an unterminated quote or with
+    /// whitespace outside of quotes, which a shell would not read as a single assignment.
+    /// If a variable is assigned twice, the last assignment wins.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut os_release = Self::default();
+
+        for (name, value) in text.lines().filter_map(parse_assignment) {
+            os_release.set(name, value);
+        }
+
+        os_release
+    }
+
+    fn set(&mut self, name: &str, value: String) {
+        let list = |value: &str| {
+            value
+                .split_whitespace()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        let field = match name {
+            "NAME" => &mut self.name,
+            "ID" => &mut self.id,
+            "PRETTY_NAME" => &mut self.pretty_name,
+            "CPE_NAME" => &mut self.cpe_name,
+            "VARIANT" => &mut self.variant,
+            "VARIANT_ID" => &mut self.variant_id,
+            "VERSION" => &mut self.version,
+            "VERSION_ID" => &mut self.version_id,
+            "VERSION_CODENAME" => &mut self.version_codename,
+            "BUILD_ID" => &mut self.build_id,
+            "IMAGE_ID" => &mut self.image_id,
+            "IMAGE_VERSION" => &mut self.image_version,
+            "HOME_URL" => &mut self.home_url,
+            "DOCUMENTATION_URL" => &mut self.documentation_url,
+            "SUPPORT_URL" => &mut self.support_url,
+            "BUG_REPORT_URL" => &mut self.bug_report_url,
+            "PRIVACY_POLICY_URL" => &mut self.privacy_policy_url,
+            "SUPPORT_END" => &mut self.support_end,
+            "LOGO" => &mut self.logo,
+            "ANSI_COLOR" => &mut self.ansi_color,
+            "VENDOR_NAME" => &mut self.vendor_name,
+            "VENDOR_URL" => &mut self.vendor_url,
+            "DEFAULT_HOSTNAME" => &mut self.default_hostname,
+            "ARCHITECTURE" => &mut self.architecture,
+            "SYSEXT_LEVEL" => &mut self.sysext_level,
+            "CONFEXT_LEVEL" => &mut self.confext_level,
+            "ID_LIKE" => {
+                self.id_like = list(value.as_str());
+                return;
+            }
+            "SYSEXT_SCOPE" => {
+                self.sysext_scope = list(value.as_str());
+                return;
+            }
+            "CONFEXT_SCOPE" => {
+                self.confext_scope = list(value.as_str());
+                return;
+            }
+            "PORTABLE_PREFIXES" => {
+                self.portable_prefixes = list(value.as_str());
+                return;
+            }
+            _ => {
+                self.extra.insert(name.to_string(), value);
+                return;
+            }
+        };
+
+        *field = Some(value);
+    }
+}
+
+/// Parses `NAME=value`, returning `None` for blank, comment and invalid lines. Like a shell, it
+/// allows no whitespace around the `=`.
+pub(in crate::lsb_release::imp) fn parse_assignment(line: &str) -> Option<(&str, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (name, value) = line.split_once('=')?;
+    let is_valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_name {
+        return None;
+    }
+
+    Some((name, unquote(value)?))
+}
+
+/// Undoes shell quoting of a single word. Returns `None` if a quote is not terminated or the value
+/// is more than one word.
+fn unquote(value: &str) -> Option<String> {
+    let mut unquoted = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => loop {
+                match chars.next()? {
+                    '\'' => break,
+                    c => unquoted.push(c),
+                }
+            },
+            '"' => loop {
+                match chars.next()? {
+                    '"' => break,
+                    '\\' => match chars.next()? {
+                        escaped @ ('$' | '`' | '"' | '\\') => unquoted.push(escaped),
+                        // other backslashes are literal within double quotes
+                        c => {
+                            unquoted.push('\\');
+                            unquoted.push(c);
+                        }
+                    },
+                    c => unquoted.push(c),
+                }
+            },
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    unquoted.push(escaped);
+                }
+            }
+            c if c.is_whitespace() => return None,
+            c => unquoted.push(c),
+        }
+    }
+
+    Some(unquoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unquotes_like_a_shell() {
+        assert_eq!(unquote("plain"), Some("plain".to_string()));
+        assert_eq!(
+            unquote("'single \\ \"quotes\"'"),
+            Some("single \\ \"quotes\"".to_string())
+        );
+        assert_eq!(
+            unquote("\"double 'quotes'\""),
+            Some("double 'quotes'".to_string())
+        );
+        assert_eq!(
+            unquote(r#""\$HOME \`id\` \"q\" \\ \n""#),
+            Some(r#"$HOME `id` "q" \ \n"#.to_string())
+        );
+        assert_eq!(
+            unquote(r"escaped\ space\$"),
+            Some("escaped space$".to_string())
+        );
+        assert_eq!(
+            unquote("con'cat'\"enated\""),
+            Some("concatenated".to_string())
+        );
+        assert_eq!(unquote(""), Some(String::new()));
+    }
+
+    #[test]
+    fn rejects_what_a_shell_would_not_assign() {
+        assert_eq!(unquote("'unterminated"), None);
+        assert_eq!(unquote("\"unterminated"), None);
+        assert_eq!(unquote("two words"), None);
+        assert_eq!(unquote("\"quoted\" word"), None);
+    }
+
+    #[test]
+    fn parses_assignments() {
+        assert_eq!(
+            parse_assignment("  ID=debian  "),
+            Some(("ID", "debian".to_string()))
+        );
+        assert_eq!(parse_assignment(""), None);
+        assert_eq!(parse_assignment("   "), None);
+        assert_eq!(parse_assignment("# ID=debian"), None);
+        assert_eq!(parse_assignment("no assignment"), None);
+        assert_eq!(parse_assignment("1ID=debian"), None);
+        assert_eq!(parse_assignment("ID-LIKE=debian"), None);
+        assert_eq!(parse_assignment("NAME=Debian GNU/Linux"), None);
+        assert_eq!(parse_assignment("ID =debian"), None);
+        assert_eq!(parse_assignment("ID= debian"), None);
+    }
+
+    #[test]
+    fn parses_a_file() {
+        let os_release = OsRelease::parse(
+            "# comment\n\
+             PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\n\
+             \n\
+             ID=debian\n\
+             ID_LIKE='ubuntu debian'\n\
+             VERSION_ID=\"11\"\n\
+             VERSION_ID=\"12\"\n\
+             NAME=Debian GNU/Linux\n\
+             UBUNTU_CODENAME=jammy\n",
+        );
+
+        assert_eq!(
+            os_release.pretty_name.as_deref(),
+            Some("Debian GNU/Linux 12 (bookworm)")
+        );
+        assert_eq!(os_release.id.as_deref(), Some("debian"));
+        assert_eq!(os_release.id_like, ["ubuntu", "debian"]);
+        assert_eq!(os_release.version_id.as_deref(), Some("12"));
+        assert_eq!(os_release.name, None);
+        assert_eq!(
+            os_release.extra.get("UBUNTU_CODENAME").map(String::as_str),
+            Some("jammy")
+        );
+    }
+}
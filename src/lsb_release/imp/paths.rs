@@ -0,0 +1,259 @@
+use crate::lsb_release::imp::distro_info::DistroInfoSource;
+use crate::lsb_release::imp::os_release::OsReleaseSource;
+use std::env::var;
+use std::fs::{read, read_link, symlink_metadata};
+use std::path::{Path, PathBuf};
+
+/// Locates every file detection reads.
+///
+/// Default locations are taken relative to `root`, so that a chroot or a mounted image can be
+/// inspected from outside. The `LSB_*` environment variables override single files of the running
+/// system; they name host paths, so they are ignored when `root` is not `/`.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub(in crate::lsb_release) struct PathGetter {
+    root: PathBuf,
+    distro_info_source: DistroInfoSource,
+}
+
+impl Default for PathGetter {
+    fn default() -> Self {
+        Self::new("/")
+    }
+}
+
+impl PathGetter {
+    pub(in crate::lsb_release) fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            distro_info_source: DistroInfoSource::default(),
+        }
+    }
+
+    /// Same as `new`, but with the distro-info tables taken from `distro_info_source`.
+    pub(in crate::lsb_release) fn with_distro_info_source(
+        self,
+        distro_info_source: DistroInfoSource,
+    ) -> Self {
+        Self {
+            distro_info_source,
+            ..self
+        }
+    }
+
+    pub(in crate::lsb_release) fn root(&self) -> &Path {
+        self.root.as_path()
+    }
+
+    fn in_root(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    /// The value of the environment variable `variable`, unless another root is inspected.
+    fn host_override(&self, variable: &str) -> Option<PathBuf> {
+        if self.root != Path::new("/") {
+            return None;
+        }
+
+        var(variable).ok().map(PathBuf::from)
+    }
+
+    fn overridable(&self, variable: &str, default: &str) -> PathBuf {
+        self.host_override(variable)
+            .unwrap_or_else(|| self.in_root(default))
+    }
+
+    /// Follows `path` if it is a symbolic link to an absolute path, which would otherwise point
+    /// outside of `root`.
+    fn resolve_in_root(&self, path: PathBuf) -> PathBuf {
+        let is_symlink = symlink_metadata(&path).is_ok_and(|m| m.file_type().is_symlink());
+        match read_link(&path) {
+            Ok(target) if is_symlink && target.is_absolute() => {
+                self.in_root(target.to_string_lossy().as_ref())
+            }
+            _ => path,
+        }
+    }
+
+    pub(in crate::lsb_release) fn lsb_os_release(&self) -> PathBuf {
+        self.os_release_source().path
+    }
+
+    /// `/etc/os-release` takes precedence over `/usr/lib/os-release`, as `os-release(5)` says.
+    pub(in crate::lsb_release) fn os_release_source(&self) -> OsReleaseSource {
+        if let Some(path) = self.host_override("LSB_OS_RELEASE") {
+            return OsReleaseSource {
+                path,
+                shadowed: None,
+            };
+        }
+
+        let etc = self.resolve_in_root(self.in_root("/etc/os-release"));
+        let usr_lib = self.resolve_in_root(self.in_root("/usr/lib/os-release"));
+        if !etc.exists() {
+            return OsReleaseSource {
+                path: usr_lib,
+                shadowed: None,
+            };
+        }
+
+        let differs = usr_lib.exists() && read(&etc).ok() != read(&usr_lib).ok();
+        OsReleaseSource {
+            path: etc,
+            shadowed: differs.then_some(usr_lib),
+        }
+    }
+
+    pub(in crate::lsb_release) fn etc_lsb_release(&self) -> PathBuf {
+        self.overridable("LSB_ETC_LSB_RELEASE", "/etc/lsb-release")
+    }
+
+    pub(in crate::lsb_release) fn etc_upstream_lsb_release(&self) -> PathBuf {
+        self.overridable(
+            "LSB_ETC_UPSTREAM_LSB_RELEASE",
+            "/etc/upstream-release/lsb-release",
+        )
+    }
+
+    pub(in crate::lsb_release) const fn distro_info_source(&self) -> DistroInfoSource {
+        self.distro_info_source
+    }
+
+    pub(in crate::lsb_release) fn known_distro_info_csv(&self, origin: &str) -> Option<PathBuf> {
+        let origin = origin.to_lowercase();
+        let path = self.in_root(format!("/usr/share/distro-info/{origin}.csv").as_str());
+        path.exists().then_some(path)
+    }
+
+    pub(in crate::lsb_release) fn lsb_module_compat(&self) -> PathBuf {
+        self.overridable(
+            "LSB_MODULE_COMPAT",
+            "/usr/share/lsb-release/module-compat.csv",
+        )
+    }
+
+    pub(in crate::lsb_release) fn debian_version(&self) -> PathBuf {
+        self.overridable("LSB_ETC_DEBIAN_VERSION", "/etc/debian_version")
+    }
+
+    pub(in crate::lsb_release) fn dpkg_origin(&self) -> PathBuf {
+        self.overridable("LSB_ETC_DPKG_ORIGINS_DEFAULT", "/etc/dpkg/origins/default")
+    }
+
+    pub(in crate::lsb_release) fn dpkg_admindir(&self) -> PathBuf {
+        // dpkg itself honors this variable
+        self.overridable("DPKG_ADMINDIR", "/var/lib/dpkg")
+    }
+
+    /// The RPM database, if there is one: `/var/lib/rpm`, or `/usr/lib/sysimage/rpm` where newer
+    /// distributions such as Fedora and openSUSE keep it.
+    pub(in crate::lsb_release) fn rpm_database(&self) -> Option<PathBuf> {
+        ["/var/lib/rpm", "/usr/lib/sysimage/rpm"]
+            .into_iter()
+            .map(|path| self.in_root(path))
+            .find(|path| path.exists())
+    }
+
+    pub(in crate::lsb_release) fn apt_lists(&self) -> PathBuf {
+        self.overridable("LSB_VAR_LIB_APT_LISTS", "/var/lib/apt/lists")
+    }
+
+    pub(in crate::lsb_release) fn apt_preferences(&self) -> PathBuf {
+        self.overridable("LSB_ETC_APT_PREFERENCES", "/etc/apt/preferences")
+    }
+
+    pub(in crate::lsb_release) fn apt_preferences_parts(&self) -> PathBuf {
+        self.overridable("LSB_ETC_APT_PREFERENCES_D", "/etc/apt/preferences.d")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsb_release::imp::test_root::TestRoot;
+
+    #[test]
+    fn rebases_default_locations() {
+        let paths = PathGetter::new("/mnt/image");
+
+        assert_eq!(
+            paths.etc_lsb_release(),
+            Path::new("/mnt/image/etc/lsb-release")
+        );
+        assert_eq!(paths.dpkg_admindir(), Path::new("/mnt/image/var/lib/dpkg"));
+        assert_eq!(
+            paths.lsb_os_release(),
+            Path::new("/mnt/image/usr/lib/os-release")
+        );
+    }
+
+    #[test]
+    fn ignores_host_overrides_in_another_root() {
+        // only this test sets the variable, so that the others see the defaults
+        std::env::set_var("LSB_ETC_UPSTREAM_LSB_RELEASE", "/tmp/upstream-lsb-release");
+
+        assert_eq!(
+            PathGetter::default().etc_upstream_lsb_release(),
+            Path::new("/tmp/upstream-lsb-release")
+        );
+        assert_eq!(
+            PathGetter::new("/mnt/image").etc_upstream_lsb_release(),
+            Path::new("/mnt/image/etc/upstream-release/lsb-release")
+        );
+    }
+
+    #[test]
+    fn prefers_etc_os_release() {
+        let root = TestRoot::new()
+            .with("etc/os-release", "ID=debian\n")
+            .with("usr/lib/os-release", "ID=debian\n");
+        let source = PathGetter::new(root.path()).os_release_source();
+
+        assert_eq!(source.path, root.path().join("etc/os-release"));
+        assert_eq!(source.shadowed, None);
+        assert_eq!(
+            source.to_string(),
+            format!("os-release is read from {}", source.path.display())
+        );
+    }
+
+    #[test]
+    fn falls_back_to_usr_lib_os_release() {
+        let root = TestRoot::new().with("usr/lib/os-release", "ID=debian\n");
+        let source = PathGetter::new(root.path()).os_release_source();
+
+        assert_eq!(source.path, root.path().join("usr/lib/os-release"));
+        assert_eq!(source.shadowed, None);
+    }
+
+    #[test]
+    fn tells_when_etc_os_release_shadows_another() {
+        let root = TestRoot::new()
+            .with("etc/os-release", "ID=linuxmint\n")
+            .with("usr/lib/os-release", "ID=ubuntu\n");
+        let source = PathGetter::new(root.path()).os_release_source();
+        let usr_lib = root.path().join("usr/lib/os-release");
+
+        assert_eq!(source.path, root.path().join("etc/os-release"));
+        assert_eq!(source.shadowed.as_ref(), Some(&usr_lib));
+        assert_eq!(
+            source.to_string(),
+            format!(
+                "os-release is read from {}, which takes precedence over {} although they differ",
+                source.path.display(),
+                usr_lib.display()
+            )
+        );
+    }
+
+    #[test]
+    fn finds_the_rpm_database() {
+        let dpkg = TestRoot::new().with("var/lib/dpkg/status", "");
+        let fedora = TestRoot::new().with("usr/lib/sysimage/rpm/rpmdb.sqlite", "");
+
+        assert_eq!(PathGetter::new(dpkg.path()).rpm_database(), None);
+        assert_eq!(
+            PathGetter::new(fedora.path()).rpm_database(),
+            Some(fedora.path().join("usr/lib/sysimage/rpm"))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::lsb_release::error::LsbError;
+use crate::lsb_release::imp::lsb::LsbPackage;
+use crate::lsb_release::imp::paths::PathGetter;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Lists the installed RPM packages which provide LSB modules, such as `redhat-lsb-core` on RHEL
+/// and Fedora or `lsb5-core` on SUSE, by asking `rpm`.
+///
+/// The `Provides` are rewritten the way dpkg writes them, such as `lsb-core-amd64 (= 4.1)`, so
+/// that they go through the same parsing as those of Debian packages.
+pub(in crate::lsb_release::imp) fn installed_lsb_packages(
+    paths: &PathGetter,
+) -> Result<Vec<LsbPackage>, LsbError> {
+    let mut rpm = Command::new("rpm");
+    if paths.root() != Path::new("/") {
+        rpm.arg("--root").arg(paths.root());
+    }
+
+    // one line per provide of every package, as `name version provide provide-version`
+    let output = rpm
+        .arg("--query")
+        .arg("--all")
+        .arg("--queryformat")
+        .arg(r"[%{NAME}\t%{VERSION}\t%{PROVIDENAME}\t%{PROVIDEVERSION}\n]")
+        .env("LC_ALL", "C.UTF-8")
+        .stderr(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .and_then(std::process::Child::wait_with_output)
+        .map_err(LsbError::command("rpm"))?;
+
+    let stdout = String::from_utf8(output.stdout).map_err(|_| LsbError::Encoding {
+        context: "`rpm --query --all` output".to_string(),
+    })?;
+
+    Ok(parse_query_output(stdout.as_str()))
+}
+
+/// Groups the lines of the query by package, keeping the LSB provides; packages without any are
+/// left out, and so are packages whose version does not start with a number.
+fn parse_query_output(output: &str) -> Vec<LsbPackage> {
+    let mut packages = BTreeMap::<(&str, &str), Vec<String>>::new();
+    for line in output.lines() {
+        let mut fields = line.split('\t');
+        let (Some(name), Some(version), Some(provide), provide_version) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if !provide.starts_with("lsb-") {
+            continue;
+        }
+
+        // `4.1` of `1:4.1-47.el8`; the LSB version has no epoch or release, and rpm prints
+        // `(none)` for a missing value
+        let provide_version = provide_version
+            .filter(|version| *version != "(none)")
+            .map(|version| version.rsplit(':').next().unwrap_or(version))
+            .and_then(|version| version.split('-').next())
+            .filter(|version| !version.is_empty());
+        let provide = provide_version.map_or_else(
+            || provide.to_string(),
+            |provide_version| format!("{provide} (= {provide_version})"),
+        );
+        packages.entry((name, version)).or_default().push(provide);
+    }
+
+    packages
+        .into_iter()
+        .filter_map(|((_, version), provides)| {
+            Some(LsbPackage {
+                lsb_version: lsb_version_of(version)?,
+                provides: provides.join(", "),
+            })
+        })
+        .collect()
+}
+
+/// The LSB version an RPM package version stands for: its leading `major.minor`, such as `4.1` of
+/// `4.1` or `5.0.2`. RPM versions are not ordered like Debian versions, so only that part is used.
+fn lsb_version_of(version: &str) -> Option<String> {
+    let end = version
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(version.len());
+    let mut numbers = version[..end]
+        .split('.')
+        .filter(|number| !number.is_empty());
+    let major = numbers.next()?;
+
+    Some(
+        numbers
+            .next()
+            .map_or_else(|| major.to_string(), |minor| format!("{major}.{minor}")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What `rpm --query --all --queryformat` prints on RHEL 8 with `redhat-lsb` and on openSUSE
+    /// with `lsb5`, trimmed to a few packages.
+    const QUERY_OUTPUT: &str = "\
+bash\t4.4.20\tbash\t4.4.20-4.el8
+bash\t4.4.20\tconfig(bash)\t4.4.20-4.el8
+filesystem\t3.8\t(none)\t(none)
+redhat-lsb-core\t4.1\tlsb-core-amd64\t4.1-47.el8
+redhat-lsb-core\t4.1\tlsb-core-noarch\t4.1-47.el8
+redhat-lsb-core\t4.1\tredhat-lsb-core(x86-64)\t4.1-47.el8
+redhat-lsb-submod-security\t4.1\tlsb-security-amd64\t(none)
+redhat-lsb-submod-security\t4.1\tlsb-security-noarch\t
+lsb5-core\t5.0.2\tlsb-core-amd64\t1:5.0-3
+lsb-broken\t(none)\tlsb-core-amd64\t
+truncated line
+";
+
+    #[test]
+    fn pairs_provides_with_their_versions() {
+        let package = |lsb_version: &str, provides: &str| LsbPackage {
+            lsb_version: lsb_version.to_string(),
+            provides: provides.to_string(),
+        };
+
+        assert_eq!(
+            parse_query_output(QUERY_OUTPUT),
+            [
+                package("5.0", "lsb-core-amd64 (= 5.0)"),
+                package("4.1", "lsb-core-amd64 (= 4.1), lsb-core-noarch (= 4.1)"),
+                package("4.1", "lsb-security-amd64, lsb-security-noarch"),
+            ]
+        );
+        assert!(parse_query_output("").is_empty());
+    }
+
+    #[test]
+    fn takes_the_leading_major_and_minor() {
+        assert_eq!(lsb_version_of("4.1").as_deref(), Some("4.1"));
+        assert_eq!(lsb_version_of("5.0.2").as_deref(), Some("5.0"));
+        assert_eq!(lsb_version_of("3.2~rc1").as_deref(), Some("3.2"));
+        assert_eq!(lsb_version_of("4").as_deref(), Some("4"));
+        assert_eq!(lsb_version_of("(none)"), None);
+        assert_eq!(lsb_version_of(""), None);
+    }
+}
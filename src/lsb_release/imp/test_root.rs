@@ -0,0 +1,42 @@
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A scratch directory standing in for the root of a system, removed when dropped.
+pub(in crate::lsb_release) struct TestRoot {
+    path: PathBuf,
+}
+
+impl TestRoot {
+    pub(in crate::lsb_release) fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "lsb-release-rs-test-{}-{}",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        create_dir_all(&path).unwrap();
+
+        Self { path }
+    }
+
+    /// Writes `contents` to `path`, which is relative to the root, creating its directories.
+    pub(in crate::lsb_release) fn with(self, path: &str, contents: &str) -> Self {
+        let path = self.path.join(path.trim_start_matches('/'));
+        create_dir_all(path.parent().unwrap()).unwrap();
+        write(path, contents).unwrap();
+
+        self
+    }
+
+    pub(in crate::lsb_release) fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+}
+
+impl Drop for TestRoot {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.path);
+    }
+}
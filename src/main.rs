@@ -1,4 +1,4 @@
-#![deny(clippy::all, clippy::string_to_string)]
+#![deny(clippy::all, clippy::implicit_clone)]
 #![warn(
     clippy::pedantic,
     clippy::nursery,
@@ -17,7 +17,7 @@
     clippy::str_to_string,
     clippy::indexing_slicing,
     clippy::unwrap_used,
-    clippy::integer_arithmetic,
+    clippy::arithmetic_side_effects,
     clippy::string_slice,
     clippy::unwrap_in_result,
     clippy::expect_used,
@@ -28,16 +28,103 @@
 )]
 
 mod args;
-mod lsb_release;
+mod distro_info;
+mod output;
+#[cfg(test)]
+mod test_info;
 
 use crate::args::Args;
-use crate::lsb_release::get::{grub_info, LSBInfo};
+use crate::distro_info::PROGRAMS;
+use crate::output::export::Assignments;
+use crate::output::ical::Calendar;
+use crate::output::json::Summary;
+use crate::output::upgrade::UpgradePath;
+use chrono::Local;
 use clap::Parser;
+use lsb_release_rs::{grub_info_with, DetectionOptions, LSBInfo, LsbModule};
+use std::path::Path;
+use std::process::exit;
 
 fn main() {
+    // this is a multi-call binary: linked as `debian-distro-info`, it behaves as that
+    let program = std::env::args_os().next().and_then(|arg0| {
+        Path::new(&arg0)
+            .file_name()
+            .and_then(|name| name.to_str().map(ToString::to_string))
+    });
+    if let Some((program, vendor)) = PROGRAMS
+        .iter()
+        .find(|(name, _)| program.as_deref() == Some(*name))
+    {
+        exit(distro_info::run(program, *vendor));
+    }
+
     let args: Args = Args::parse();
     let args = args.set_implied_flags();
-    let grub = grub_info();
+
+    let mut options = DetectionOptions::default();
+    options.root.clone_from(&args.root);
+    options.upstream = args.upstream;
+    options.distro_info_source = args.distro_info_source;
+    options.date = args.date;
+    let grub = grub_info_with(&options);
+    print_info(&args, &grub);
+}
+
+fn print_info(args: &Args, grub: &impl LSBInfo) {
+    if args.verbose {
+        for diagnostic in grub.diagnostics() {
+            eprintln!("{diagnostic}");
+        }
+    }
+
+    if let Some(ref target) = args.upgrade_path {
+        let date = args.date.unwrap_or_else(|| Local::now().date_naive());
+        match UpgradePath::new(grub, target.as_deref(), date) {
+            Err(e) => {
+                eprintln!("{e}");
+                exit(1);
+            }
+            Ok(path) if args.json => println!("{}", path.to_json_string()),
+            Ok(path) if args.show_in_short_format => {
+                if !path.is_empty() {
+                    println!("{}", path.to_short_string());
+                }
+            }
+            Ok(path) => println!("{}", path.to_long_string()),
+        }
+        return;
+    }
+
+    if args.json {
+        println!("{}", Summary::new(grub).to_json_string());
+        return;
+    }
+
+    if let Some(ref template) = args.format {
+        println!("{}", template.render(grub));
+        return;
+    }
+
+    if args.export {
+        println!(
+            "{}",
+            Assignments::new(grub, args.export_prefix.as_str()).to_shell_string()
+        );
+        return;
+    }
+
+    if args.ical {
+        match Calendar::new(grub) {
+            None => {
+                eprintln!("This release is not listed in distro-info.");
+                exit(1);
+            }
+            Some(calendar) => print!("{}", calendar.to_ics_string()),
+        }
+        return;
+    }
+
     let short = args.show_in_short_format;
     let na = "n/a".to_string();
 
@@ -47,7 +134,7 @@ fn main() {
                 eprintln!("No LSB modules are available.");
             }
             Some(lsb_version) => {
-                let v = lsb_version.join(":");
+                let v = LsbModule::join(&lsb_version);
                 if short {
                     println!("{v}");
                 } else {
@@ -96,4 +183,51 @@ fn main() {
             println!("Codename:\t{v}");
         }
     }
+
+    if args.show_eol {
+        let v = grub
+            .distro_release()
+            .and_then(|release| release.eol)
+            .map_or_else(|| na.clone(), |eol| eol.to_string());
+
+        if short {
+            println!("{v}");
+        } else {
+            println!("EOL:\t\t{v}");
+        }
+    }
+
+    if args.show_support_status {
+        let status = grub.distro_release().map(|release| {
+            release.support_status(args.date.unwrap_or_else(|| Local::now().date_naive()))
+        });
+
+        if short {
+            // `<phase> <until> <days remaining>`, so that it can be split by `read`
+            let v = status.map_or_else(
+                || na.clone(),
+                |status| {
+                    let until = status.until.map_or_else(|| na.clone(), |d| d.to_string());
+                    let days = status
+                        .days_remaining
+                        .map_or_else(|| na.clone(), |d| d.to_string());
+                    format!("{} {until} {days}", status.phase)
+                },
+            );
+            println!("{v}");
+        } else {
+            let v = status.map_or_else(|| na.clone(), |status| status.to_string());
+            println!("Support status:\t{v}");
+        }
+    }
+
+    if args.show_testing_codename {
+        let v = grub.testing_codename().unwrap_or_else(|| na.clone());
+
+        if short {
+            println!("{v}");
+        } else {
+            println!("Testing codename:\t{v}");
+        }
+    }
 }
@@ -0,0 +1,4 @@
+pub mod export;
+pub mod ical;
+pub mod json;
+pub mod upgrade;
@@ -0,0 +1,137 @@
+use lsb_release_rs::{LSBInfo, LsbModule};
+
+/// Renders the fields as `KEY='value'` lines, which can be evaluated by any POSIX shell:
+///
+/// ```sh
+/// eval "$(lsb_release --export)"
+/// ```
+///
+/// Every variable is always emitted; missing values become an empty string.
+pub struct Assignments<'p> {
+    prefix: &'p str,
+    variables: Vec<(&'static str, String)>,
+}
+
+impl<'p> Assignments<'p> {
+    pub fn new(info: &impl LSBInfo, prefix: &'p str) -> Self {
+        let variables = vec![
+            ("ID", info.id().unwrap_or_default()),
+            ("DESCRIPTION", info.description().unwrap_or_default()),
+            ("RELEASE", info.release().unwrap_or_default()),
+            ("CODENAME", info.codename().unwrap_or_default()),
+            (
+                "MODULES",
+                info.lsb_version()
+                    .as_deref()
+                    .map(LsbModule::join)
+                    .unwrap_or_default(),
+            ),
+        ];
+
+        Self { prefix, variables }
+    }
+
+    pub fn to_shell_string(&self) -> String {
+        self.variables
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "{prefix}{name}={value}",
+                    prefix = self.prefix,
+                    value = shell_quote(value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Quotes `value` for POSIX sh.
+///
+/// Everything between single quotes is taken literally, so the only character that needs care is
+/// the single quote itself: it is closed, emitted as `\'`, and reopened.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Checks that `prefix` keeps every generated name a valid shell variable name.
+pub fn validate_prefix(prefix: &str) -> Result<(), String> {
+    let valid_chars = prefix
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let starts_with_digit = prefix.starts_with(|c: char| c.is_ascii_digit());
+
+    if valid_chars && !starts_with_digit {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{prefix}` is not a valid shell variable prefix: use only ASCII letters, digits and `_`, and do not start with a digit"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::FixedInfo;
+
+    fn fixed() -> FixedInfo {
+        FixedInfo {
+            description: Some("Debian GNU/Linux 12 (bookworm) \"$HOME\" it's".to_string()),
+            codename: None,
+            ..FixedInfo::bookworm()
+        }
+    }
+
+    #[test]
+    fn quotes_for_posix_sh() {
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("$HOME `id` \\"), "'$HOME `id` \\'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn prints_every_variable() {
+        let lines = Assignments::new(&fixed(), "LSB_").to_shell_string();
+
+        assert_eq!(
+            lines.lines().collect::<Vec<_>>(),
+            [
+                "LSB_ID='Debian'",
+                r#"LSB_DESCRIPTION='Debian GNU/Linux 12 (bookworm) "$HOME" it'\''s'"#,
+                "LSB_RELEASE='12'",
+                "LSB_CODENAME=''",
+                "LSB_MODULES='core-4.1-amd64:core-4.1-noarch'",
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluates_back_to_the_values() {
+        let script = format!(
+            "{}\nprintf '%s' \"$X_DESCRIPTION\"",
+            Assignments::new(&fixed(), "X_").to_shell_string()
+        );
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .output()
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            fixed().description().unwrap()
+        );
+    }
+
+    #[test]
+    fn accepts_only_shell_variable_prefixes() {
+        for prefix in ["", "LSB_", "_x", "OS2_"] {
+            assert!(validate_prefix(prefix).is_ok(), "{prefix}");
+        }
+        for prefix in ["2LSB_", "LSB-", "LSB ", "LSB$", "ÄÖ_"] {
+            assert!(validate_prefix(prefix).is_err(), "{prefix}");
+        }
+    }
+}
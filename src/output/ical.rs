@@ -0,0 +1,193 @@
+use chrono::{NaiveDate, Utc};
+use lsb_release_rs::{DistroRelease, LSBInfo};
+
+/// The calendar printed by `--ical`: an RFC 5545 all-day event for every milestone of the
+/// running release and of the releases after it.
+///
+/// Each event has a stable `UID`, such as `debian-bookworm-eol@lsb-release-rs`, so that importing
+/// an updated calendar replaces the events instead of duplicating them.
+pub struct Calendar {
+    events: Vec<Event>,
+}
+
+struct Event {
+    uid: String,
+    date: NaiveDate,
+    summary: String,
+}
+
+impl Calendar {
+    /// Returns `None` if the running release is not in the distro-info table.
+    pub fn new(info: &impl LSBInfo) -> Option<Self> {
+        let table = info.distro_info_table()?;
+        let running = info.distro_release()?;
+        let distributor = info.id().unwrap_or_else(|| "n/a".to_string());
+
+        let events = table
+            .releases()
+            .iter()
+            .skip_while(|release| release.series != running.series)
+            .flat_map(|release| Self::milestones(distributor.as_str(), release))
+            .collect();
+
+        Some(Self { events })
+    }
+
+    fn milestones(distributor: &str, release: &DistroRelease) -> Vec<Event> {
+        let name = format!(
+            "{distributor} {version} \"{codename}\"",
+            version = release.version.as_ref().unwrap_or(&release.series),
+            codename = release.codename
+        );
+        let milestones = [
+            ("release", release.release, "released"),
+            ("eol", release.eol, "end of regular security support"),
+            ("eol-server", release.eol_server, "end of server support"),
+            ("eol-lts", release.eol_lts, "end of LTS"),
+            ("eol-elts", release.eol_elts, "end of ELTS"),
+            ("eol-esm", release.eol_esm, "end of ESM"),
+            ("eol-legacy", release.eol_legacy, "end of Legacy support"),
+        ];
+
+        milestones
+            .into_iter()
+            .filter_map(|(key, date, what)| {
+                Some(Event {
+                    uid: format!(
+                        "{}-{}-{key}@lsb-release-rs",
+                        distributor.to_lowercase(),
+                        release.series
+                    ),
+                    date: date?,
+                    summary: format!("{name} {what}"),
+                })
+            })
+            .collect()
+    }
+
+    pub fn to_ics_string(&self) -> String {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//lsb-release-rs//release calendar//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        for event in &self.events {
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}", event.uid),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+                format!("SUMMARY:{}", escape_text(event.summary.as_str())),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        // iCalendar lines end with CRLF, including the last one
+        lines
+            .iter()
+            .map(|line| fold(line.as_str()) + "\r\n")
+            .collect()
+    }
+}
+
+// RFC 5545, section 3.3.11
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545, section 3.1: lines longer than 75 octets continue on lines starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::FixedInfo;
+
+    #[test]
+    fn lists_milestones_from_the_running_release_on() {
+        let ics = Calendar::new(&FixedInfo::bookworm())
+            .unwrap()
+            .to_ics_string();
+        let lines = ics
+            .split("\r\n")
+            .filter(|line| !line.starts_with("DTSTAMP:"))
+            .collect::<Vec<_>>();
+
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(
+            lines[..9],
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//lsb-release-rs//release calendar//EN",
+                "CALSCALE:GREGORIAN",
+                "BEGIN:VEVENT",
+                "UID:debian-bookworm-release@lsb-release-rs",
+                "DTSTART;VALUE=DATE:20230610",
+                "SUMMARY:Debian 12 \"Bookworm\" released",
+                "TRANSP:TRANSPARENT",
+            ]
+        );
+        assert_eq!(
+            lines
+                .iter()
+                .filter_map(|line| line.strip_prefix("UID:"))
+                .collect::<Vec<_>>(),
+            [
+                "debian-bookworm-release@lsb-release-rs",
+                "debian-bookworm-eol@lsb-release-rs",
+                "debian-bookworm-eol-lts@lsb-release-rs",
+                "debian-bookworm-eol-elts@lsb-release-rs",
+                "debian-trixie-release@lsb-release-rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn needs_the_running_release_in_the_table() {
+        let buster = FixedInfo {
+            codename: Some("buster".to_string()),
+            ..FixedInfo::bookworm()
+        };
+
+        assert!(Calendar::new(&buster).is_none());
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(escape_text("a;b,c\\d\ne"), r"a\;b\,c\\d\ne");
+    }
+
+    #[test]
+    fn folds_long_lines_between_characters() {
+        let ascii = "a".repeat(80);
+        assert_eq!(
+            fold(ascii.as_str()),
+            format!("{}\r\n {}", "a".repeat(75), "a".repeat(5))
+        );
+
+        let accented = format!("{}é", "a".repeat(74));
+        assert_eq!(fold(accented.as_str()), format!("{}\r\n é", "a".repeat(74)));
+        assert_eq!(fold("short"), "short");
+    }
+}
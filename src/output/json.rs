@@ -0,0 +1,93 @@
+use lsb_release_rs::LSBInfo;
+use serde::Serialize;
+
+/// Version of the `--json` output schema.
+///
+/// Bump this whenever a field is removed, renamed or changes its type.
+/// Adding a new field does not require a bump.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// The object printed by `--json`. Its help text documents it for users; keep both in sync.
+///
+/// Schema version 1:
+///
+/// | key              | type                     | note                              |
+/// |------------------|--------------------------|-----------------------------------|
+/// | `schema_version` | number                   | always `1` for this layout        |
+/// | `id`             | string or `null`         | same as `-i`                      |
+/// | `description`    | string or `null`         | same as `-d`                      |
+/// | `release`        | string or `null`         | same as `-r`                      |
+/// | `codename`       | string or `null`         | same as `-c`                      |
+/// | `lsb_modules`    | array of string or `null`| same as `-v`, `null` if none      |
+///
+/// Unlike the human-readable output, missing values are `null` rather than `"n/a"`.
+#[derive(Serialize)]
+pub struct Summary {
+    schema_version: u32,
+    id: Option<String>,
+    description: Option<String>,
+    release: Option<String>,
+    codename: Option<String>,
+    lsb_modules: Option<Vec<String>>,
+}
+
+impl Summary {
+    pub fn new(info: &impl LSBInfo) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            id: info.id(),
+            description: info.description(),
+            release: info.release(),
+            codename: info.codename(),
+            lsb_modules: info
+                .lsb_version()
+                .map(|modules| modules.iter().map(ToString::to_string).collect()),
+        }
+    }
+
+    pub fn to_json_string(&self) -> String {
+        // every field is a plain string, number or sequence of them, which always serializes
+        serde_json::to_string(self).expect("JSON serialization cannot fail here")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_info::FixedInfo;
+    use serde_json::{json, Value};
+
+    fn to_value(info: &impl LSBInfo) -> Value {
+        serde_json::from_str(Summary::new(info).to_json_string().as_str()).unwrap()
+    }
+
+    #[test]
+    fn prints_every_key() {
+        assert_eq!(
+            to_value(&FixedInfo::bookworm()),
+            json!({
+                "schema_version": 1,
+                "id": "Debian",
+                "description": "Debian GNU/Linux 12 (bookworm)",
+                "release": "12",
+                "codename": "bookworm",
+                "lsb_modules": ["core-4.1-amd64", "core-4.1-noarch"],
+            })
+        );
+    }
+
+    #[test]
+    fn prints_unknown_values_as_null() {
+        assert_eq!(
+            to_value(&FixedInfo::default()),
+            json!({
+                "schema_version": JSON_SCHEMA_VERSION,
+                "id": null,
+                "description": null,
+                "release": null,
+                "codename": null,
+                "lsb_modules": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::NaiveDate;
+use lsb_release_rs::{DistroRelease, LSBInfo};
+use serde::Serialize;
+
+/// The releases printed by `--upgrade-path`: each one the system has to be upgraded to, in turn,
+/// to get from the running release to the target.
+///
+/// With `--json` it is printed as an object with `from`, `to` and `hops`, each release being an
+/// object with `version`, `codename`, `series`, `release` and `eol`; unknown values are `null`.
+#[derive(Serialize)]
+pub struct UpgradePath {
+    from: Hop,
+    to: Hop,
+    hops: Vec<Hop>,
+}
+
+#[derive(Serialize)]
+struct Hop {
+    version: Option<String>,
+    codename: String,
+    series: String,
+    release: Option<NaiveDate>,
+    eol: Option<NaiveDate>,
+}
+
+impl From<&DistroRelease> for Hop {
+    fn from(release: &DistroRelease) -> Self {
+        Self {
+            version: release.version.clone(),
+            codename: release.codename.clone(),
+            series: release.series.clone(),
+            release: release.release,
+            eol: release.eol,
+        }
+    }
+}
+
+impl UpgradePath {
+    /// Plans the upgrade to `target`, which is looked up like `distro-info` names, such as
+    /// `trixie`, `13` or `testing`, or to the newest stable release as of `date`.
+    pub fn new(info: &impl LSBInfo, target: Option<&str>, date: NaiveDate) -> Result<Self, String> {
+        let table = info
+            .distro_info_table()
+            .ok_or("This distribution has no distro-info table.")?;
+        let from = info
+            .distro_release()
+            .ok_or("This release is not listed in distro-info.")?;
+        let to = match target {
+            Some(name) => table
+                .lookup(name, date)
+                .ok_or_else(|| format!("Unknown target release: {name}"))?,
+            None => table
+                .by_alias("stable", date)
+                .ok_or("No stable release is listed in distro-info.")?,
+        };
+
+        Ok(Self {
+            hops: table
+                .upgrade_path(&from, to)
+                .into_iter()
+                .map(Hop::from)
+                .collect(),
+            from: Hop::from(&from),
+            to: Hop::from(to),
+        })
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+
+    pub fn to_json_string(&self) -> String {
+        // every field is a plain string or date, which always serializes
+        serde_json::to_string(self).expect("JSON serialization cannot fail here")
+    }
+
+    /// One line per hop: `<version> <series> <release date> <eol>`, with `n/a` for unknown values,
+    /// so that it can be split by `read`.
+    pub fn to_short_string(&self) -> String {
+        self.hops
+            .iter()
+            .map(|hop| {
+                format!(
+                    "{} {} {} {}",
+                    na(hop
+                        .version
+                        .as_deref()
+                        .and_then(|v| v.split_whitespace().next())),
+                    hop.series,
+                    na(hop.release),
+                    na(hop.eol)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_long_string(&self) -> String {
+        if self.is_empty() {
+            return format!(
+                "Upgrade path:\tnone, {} is not older than {}",
+                self.from.name(),
+                self.to.name()
+            );
+        }
+
+        let mut lines = vec![format!(
+            "Upgrade path:\t{} -> {}",
+            self.from.name(),
+            self.to.name()
+        )];
+        lines.extend(self.hops.iter().map(|hop| {
+            format!(
+                "\t\t{}: released {}, EOL {}",
+                hop.name(),
+                na(hop.release),
+                na(hop.eol)
+            )
+        }));
+
+        lines.join("\n")
+    }
+}
+
+impl Hop {
+    /// Such as `12 (bookworm)`.
+    fn name(&self) -> String {
+        self.version.as_ref().map_or_else(
+            || self.series.clone(),
+            |version| format!("{version} ({})", self.series),
+        )
+    }
+}
+
+fn na(value: Option<impl ToString>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |value| value.to_string())
+}
@@ -0,0 +1,77 @@
+//! A fixed [`LSBInfo`] for the tests of both the library and the binary, which each include this
+//! file as a module of their own.
+
+use lsb_release_rs::{DistroInfoTable, DistroRelease, LSBInfo, LsbModule};
+
+/// Part of the Debian distro-info table: two stable releases, one released after them, one in
+/// development and sid.
+pub const DEBIAN_CSV: &str = "\
+version,codename,series,created,release,eol,eol-lts,eol-elts
+11,Bullseye,bullseye,2019-07-06,2021-08-14,2024-08-14,2026-08-31,2031-06-30
+12,Bookworm,bookworm,2021-08-14,2023-06-10,2026-07-11,2028-06-30,2033-06-30
+13,Trixie,trixie,2023-06-10,2025-08-09
+14,Forky,forky,2025-08-09
+,Sid,sid,1993-08-16
+";
+
+/// Answers every question with the values it was built with.
+#[derive(Default)]
+pub struct FixedInfo {
+    pub id: Option<String>,
+    pub description: Option<String>,
+    pub release: Option<String>,
+    pub codename: Option<String>,
+    pub lsb_version: Option<Vec<LsbModule>>,
+    /// Also gives the [`LSBInfo::distro_release`], the row of `codename`.
+    pub table: Option<DistroInfoTable>,
+}
+
+impl FixedInfo {
+    /// Debian 12 with the `core` modules of LSB 4.1, described by [`DEBIAN_CSV`].
+    pub fn bookworm() -> Self {
+        Self {
+            id: Some("Debian".to_string()),
+            description: Some("Debian GNU/Linux 12 (bookworm)".to_string()),
+            release: Some("12".to_string()),
+            codename: Some("bookworm".to_string()),
+            lsb_version: Some(vec![
+                LsbModule::new("core", "4.1", "amd64"),
+                LsbModule::new("core", "4.1", "noarch"),
+            ]),
+            table: Some(DistroInfoTable::parse(DEBIAN_CSV.as_bytes()).unwrap()),
+        }
+    }
+}
+
+impl LSBInfo for FixedInfo {
+    fn id(&self) -> Option<String> {
+        self.id.clone()
+    }
+
+    fn description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn release(&self) -> Option<String> {
+        self.release.clone()
+    }
+
+    fn codename(&self) -> Option<String> {
+        self.codename.clone()
+    }
+
+    fn lsb_version(&self) -> Option<Vec<LsbModule>> {
+        self.lsb_version.clone()
+    }
+
+    fn distro_release(&self) -> Option<DistroRelease> {
+        self.table
+            .as_ref()?
+            .by_series(self.codename.as_ref()?)
+            .cloned()
+    }
+
+    fn distro_info_table(&self) -> Option<DistroInfoTable> {
+        self.table.clone()
+    }
+}